@@ -0,0 +1,136 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A builder for registering behaviours with a mock state without the **galvanic-mock** macros.
+//!
+//! ```ignore
+//! MockBuilder::new(&mock).given("Animal", "legs").matching(eq(4)).returning_stmt(0)
+//!                        .expect("Animal", "name").times(2);
+//! ```
+//!
+//! A matcher passed to `matching()` is stored as the behaviour's bound variables,
+//! a hand-rolled mock retrieves it with `bound_as()` to check the arguments of a call.
+
+use std::any::Any;
+use std::rc::Rc;
+
+use {ExpectBehaviour, GivenBehaviour, MockControl, MockState};
+
+/// Registers behaviours with a mock state, see `given()` and `expect()`.
+#[derive(Clone, Copy)]
+pub struct MockBuilder<'m> {
+    mock: &'m MockState,
+}
+
+impl<'m> MockBuilder<'m> {
+    /// Creates a builder registering behaviours with the `mock`.
+    pub fn new(mock: &'m MockState) -> Self {
+        MockBuilder { mock }
+    }
+
+    /// Starts the definition of a *given* behaviour for the trait's method.
+    pub fn given(self, requested_trait: &'static str, method: &'static str) -> GivenSetup<'m> {
+        GivenSetup {
+            builder: self,
+            requested_trait,
+            method,
+            bound: None,
+            stmt_repr: format!("given {}::{}", requested_trait, method),
+        }
+    }
+
+    /// Starts the definition of an *expected* behaviour for the trait's method.
+    pub fn expect(self, requested_trait: &'static str, method: &'static str) -> ExpectSetup<'m> {
+        ExpectSetup {
+            builder: self,
+            requested_trait,
+            method,
+            stmt_id: 0,
+            bound: None,
+            stmt_repr: format!("expect {}::{}", requested_trait, method),
+        }
+    }
+}
+
+/// The definition of a *given* behaviour in progress, created by `MockBuilder::given()`.
+pub struct GivenSetup<'m> {
+    builder: MockBuilder<'m>,
+    requested_trait: &'static str,
+    method: &'static str,
+    bound: Option<Rc<dyn Any>>,
+    stmt_repr: String,
+}
+
+impl<'m> GivenSetup<'m> {
+    /// Sets the matcher of the call's arguments, the behaviour matches any arguments otherwise.
+    pub fn matching<M: 'static>(mut self, matcher: M) -> Self {
+        self.bound = Some(Rc::new(matcher));
+        self
+    }
+
+    /// Sets the string representation of the behaviour's definition.
+    pub fn repr(mut self, stmt_repr: &str) -> Self {
+        self.stmt_repr = stmt_repr.to_string();
+        self
+    }
+
+    /// Registers the behaviour with the id of the statement producing its return value.
+    pub fn returning_stmt(self, stmt_id: usize) -> MockBuilder<'m> {
+        let behaviour = match self.bound {
+            Some(bound) => GivenBehaviour::with(stmt_id, bound, &self.stmt_repr),
+            None => GivenBehaviour::always(stmt_id, &self.stmt_repr),
+        };
+        self.builder.mock.add_given_behaviour(self.requested_trait, self.method, behaviour);
+        self.builder
+    }
+}
+
+/// The definition of an *expected* behaviour in progress, created by `MockBuilder::expect()`.
+pub struct ExpectSetup<'m> {
+    builder: MockBuilder<'m>,
+    requested_trait: &'static str,
+    method: &'static str,
+    stmt_id: usize,
+    bound: Option<Rc<dyn Any>>,
+    stmt_repr: String,
+}
+
+impl<'m> ExpectSetup<'m> {
+    /// Sets the matcher of the call's arguments, the behaviour matches any arguments otherwise.
+    pub fn matching<M: 'static>(mut self, matcher: M) -> Self {
+        self.bound = Some(Rc::new(matcher));
+        self
+    }
+
+    /// Sets the unique id of the behaviour within the mocked method to which it belongs.
+    pub fn stmt_id(mut self, stmt_id: usize) -> Self {
+        self.stmt_id = stmt_id;
+        self
+    }
+
+    /// Sets the string representation of the behaviour's definition.
+    pub fn repr(mut self, stmt_repr: &str) -> Self {
+        self.stmt_repr = stmt_repr.to_string();
+        self
+    }
+
+    /// Registers the behaviour expecting exactly `times` matches.
+    pub fn times(self, times: usize) -> MockBuilder<'m> {
+        let bound = self.bound.unwrap_or_else(|| Rc::new(()));
+        let behaviour = ExpectBehaviour::with_times(times, self.stmt_id, bound, &self.stmt_repr);
+        self.builder.mock.add_expect_behaviour(self.requested_trait, self.method, behaviour);
+        self.builder
+    }
+}
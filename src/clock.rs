@@ -0,0 +1,93 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Sources of the current time used by the timing features of a mock.
+//!
+//! All time readings of a `MockState` and its behaviours go through its clock, see `MockState::set_clock()`.
+//! Tests inject a `ManualClock` to make timestamps, time to live, and timeouts deterministic.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A source of the current time.
+pub trait Clock {
+    /// Returns the current time of the clock.
+    fn now(&self) -> Instant;
+}
+
+/// A clock reading the real monotonic time, the default clock of a mock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock which only advances when told to.
+///
+/// Clones share the same time, i.e., a test can keep a clone to advance the clock of a mock.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl ManualClock {
+    /// Creates a clock which stands still at the current real time.
+    pub fn new() -> Self {
+        ManualClock { now: Rc::new(Cell::new(Instant::now())) }
+    }
+
+    /// Advances the clock by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+/// A counter handing out the sequence numbers of the matches of the mocks attached to it.
+///
+/// Clones share the same counter. See `MockState::attach_sequence_clock()`.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceClock {
+    next: Rc<Cell<usize>>,
+}
+
+impl SequenceClock {
+    /// Creates a counter starting at `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the next sequence number.
+    pub fn tick(&self) -> usize {
+        let sequence_number = self.next.get();
+        self.next.set(sequence_number + 1);
+        sequence_number
+    }
+}
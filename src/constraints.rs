@@ -0,0 +1,235 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Constraints on the arguments an *expected* behaviour is matched with across several calls.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// A stateful check of the arguments passed to `ExpectBehaviour::matched_with_args()`.
+pub trait ArgsConstraint {
+    /// Checks the arguments of the behaviour's `call_index`-th match (starting at `0`).
+    ///
+    /// Returns a description of the violation if the arguments break the constraint.
+    fn check(&self, call_index: usize, args: &dyn Any) -> Result<(), String>;
+
+    /// Returns a copy of the constraint which has not seen any arguments yet.
+    fn fresh(&self) -> Box<dyn ArgsConstraint>;
+
+    /// Returns a description of the requirements which the arguments seen so far do not meet, if any.
+    ///
+    /// Unlike a violation an unmet requirement can still be met by later matches.
+    fn unmet_requirements(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Relates two arguments of a behaviour.
+pub type Comparator<T> = Rc<dyn Fn(&T, &T) -> bool>;
+
+/// Requires that each argument is related to the previous one by a comparator.
+pub struct MonotonicArgs<T> {
+    cmp: Comparator<T>,
+    previous: RefCell<Option<T>>,
+}
+
+impl<T> MonotonicArgs<T> {
+    pub fn new(cmp: Comparator<T>) -> Self {
+        Self { cmp, previous: RefCell::new(None) }
+    }
+}
+
+impl<T: Clone + 'static> ArgsConstraint for MonotonicArgs<T> {
+    fn check(&self, _call_index: usize, args: &dyn Any) -> Result<(), String> {
+        let args = downcast_args::<T>(args)?;
+        let mut previous = self.previous.borrow_mut();
+        let result = match *previous {
+            Some(ref previous) if !(self.cmp)(previous, args) => {
+                Err("arguments are out of order with respect to the previous match".to_string())
+            }
+            _ => Ok(()),
+        };
+        *previous = Some(args.clone());
+        result
+    }
+
+    fn fresh(&self) -> Box<dyn ArgsConstraint> {
+        Box::new(MonotonicArgs::new(self.cmp.clone()))
+    }
+}
+
+/// Requires that each argument differs from the previous one according to an equality comparator.
+pub struct ChangingArgs<T> {
+    eq: Comparator<T>,
+    previous: RefCell<Option<T>>,
+}
+
+impl<T> ChangingArgs<T> {
+    pub fn new(eq: Comparator<T>) -> Self {
+        Self { eq, previous: RefCell::new(None) }
+    }
+}
+
+impl<T: Clone + 'static> ArgsConstraint for ChangingArgs<T> {
+    fn check(&self, _call_index: usize, args: &dyn Any) -> Result<(), String> {
+        let args = downcast_args::<T>(args)?;
+        let mut previous = self.previous.borrow_mut();
+        let result = match *previous {
+            Some(ref previous) if (self.eq)(previous, args) => {
+                Err("arguments are unchanged with respect to the previous match".to_string())
+            }
+            _ => Ok(()),
+        };
+        *previous = Some(args.clone());
+        result
+    }
+
+    fn fresh(&self) -> Box<dyn ArgsConstraint> {
+        Box::new(ChangingArgs::new(self.eq.clone()))
+    }
+}
+
+/// Requires that each of a set of arguments is seen at least once, in any order.
+pub struct RequiredArgsSet<T> {
+    required: Rc<Vec<T>>,
+    eq: Comparator<T>,
+    seen: RefCell<Vec<bool>>,
+}
+
+impl<T> RequiredArgsSet<T> {
+    pub fn new(required: Rc<Vec<T>>, eq: Comparator<T>) -> Self {
+        let seen = RefCell::new(vec![false; required.len()]);
+        Self { required, eq, seen }
+    }
+}
+
+impl<T: Debug + 'static> ArgsConstraint for RequiredArgsSet<T> {
+    fn check(&self, _call_index: usize, args: &dyn Any) -> Result<(), String> {
+        let args = downcast_args::<T>(args)?;
+        let mut seen = self.seen.borrow_mut();
+        for (required, seen) in self.required.iter().zip(seen.iter_mut()) {
+            if (self.eq)(required, args) {
+                *seen = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn fresh(&self) -> Box<dyn ArgsConstraint> {
+        Box::new(RequiredArgsSet::new(self.required.clone(), self.eq.clone()))
+    }
+
+    fn unmet_requirements(&self) -> Option<String> {
+        let missing: Vec<String> = self.required
+                                       .iter()
+                                       .zip(self.seen.borrow().iter())
+                                       .filter(|&(_, &seen)| !seen)
+                                       .map(|(required, _)| format!("{:?}", required))
+                                       .collect();
+        if missing.is_empty() {
+            None
+        } else {
+            Some(format!("required arguments never observed: {}", missing.join(", ")))
+        }
+    }
+}
+
+/// Computes the hash of the key identifying an argument.
+pub type KeyHasher<T> = Rc<dyn Fn(&T) -> u64>;
+
+/// Returns a hasher of the keys `key_fn` extracts from the arguments.
+pub fn key_hasher<T, K, F>(key_fn: F) -> KeyHasher<T>
+    where K: Hash,
+          F: Fn(&T) -> K + 'static
+{
+    Rc::new(move |args: &T| {
+        let mut hasher = DefaultHasher::new();
+        key_fn(args).hash(&mut hasher);
+        hasher.finish()
+    })
+}
+
+/// Requires that at least a number of arguments with distinct keys is seen.
+pub struct DistinctArgs<T> {
+    at_least: usize,
+    key_hash: KeyHasher<T>,
+    seen: RefCell<HashSet<u64>>,
+}
+
+impl<T> DistinctArgs<T> {
+    pub fn new(at_least: usize, key_hash: KeyHasher<T>) -> Self {
+        Self { at_least, key_hash, seen: RefCell::new(HashSet::new()) }
+    }
+}
+
+impl<T: 'static> ArgsConstraint for DistinctArgs<T> {
+    fn check(&self, _call_index: usize, args: &dyn Any) -> Result<(), String> {
+        let args = downcast_args::<T>(args)?;
+        self.seen.borrow_mut().insert((self.key_hash)(args));
+        Ok(())
+    }
+
+    fn fresh(&self) -> Box<dyn ArgsConstraint> {
+        Box::new(DistinctArgs::new(self.at_least, self.key_hash.clone()))
+    }
+
+    fn unmet_requirements(&self) -> Option<String> {
+        let num_distinct = self.seen.borrow().len();
+        if num_distinct >= self.at_least {
+            None
+        } else {
+            Some(format!("only {} of at least {} distinct arguments observed", num_distinct, self.at_least))
+        }
+    }
+}
+
+/// Requires that no two arguments share the same key.
+pub struct AllDistinctArgs<T> {
+    key_hash: KeyHasher<T>,
+    seen: RefCell<HashMap<u64, usize>>,
+}
+
+impl<T> AllDistinctArgs<T> {
+    pub fn new(key_hash: KeyHasher<T>) -> Self {
+        Self { key_hash, seen: RefCell::new(HashMap::new()) }
+    }
+}
+
+impl<T: 'static> ArgsConstraint for AllDistinctArgs<T> {
+    fn check(&self, call_index: usize, args: &dyn Any) -> Result<(), String> {
+        let args = downcast_args::<T>(args)?;
+        match *self.seen.borrow_mut().entry((self.key_hash)(args)).or_insert(call_index) {
+            first_index if first_index != call_index => {
+                Err(format!("arguments repeat the key of the arguments of match #{}", first_index))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn fresh(&self) -> Box<dyn ArgsConstraint> {
+        Box::new(AllDistinctArgs::new(self.key_hash.clone()))
+    }
+}
+
+/// Downcasts the type-erased arguments to the type expected by a constraint.
+fn downcast_args<T: 'static>(args: &dyn Any) -> Result<&T, String> {
+    args.downcast_ref::<T>()
+        .ok_or_else(|| "arguments are not of the type expected by the behaviour's constraint".to_string())
+}
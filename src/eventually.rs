@@ -0,0 +1,163 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Asynchronous verification which awaits the expected calls.
+//!
+//! The module is available with the `async` feature.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use {MockState, VerificationError};
+
+/// The future returned by `MockState::verify_eventually()`.
+///
+/// The future does not depend on a specific async runtime:
+/// * Whenever it is polled the mock's verification is evaluated. If it succeeds the future resolves to `Ok(())`.
+/// * Otherwise the task's waker is registered with the mock and woken by the next match of any of its behaviours.
+/// * A timer thread wakes the task once the time remaining until the deadline has elapsed in real time.
+///   The deadline itself is measured by the mock's clock, see `MockState::set_clock()`. While the deadline has not been
+///   reached the timer is re-armed on every poll after it fired, i.e., with a `ManualClock` which is advanced past the
+///   deadline the future times out within one further timeout.
+///   If the behaviours are still unsatisfied when polled after the deadline the future resolves to
+///   `VerificationError::Timeout` with the verification's failures, it never waits beyond the deadline for further matches.
+///
+/// Unlike `MockControl::verify()` neither the message sink is notified nor a panic raised.
+pub struct VerifyEventually<'m> {
+    mock: &'m MockState,
+    timeout: Duration,
+    deadline: Instant,
+    timer_waker: Option<Arc<Mutex<Option<Waker>>>>,
+}
+
+impl<'m> VerifyEventually<'m> {
+    pub(crate) fn new(mock: &'m MockState, timeout: Duration) -> Self {
+        VerifyEventually {
+            mock,
+            timeout,
+            deadline: mock.now() + timeout,
+            timer_waker: None,
+        }
+    }
+
+    /// Makes sure the task is woken once the deadline is reached, re-arming the timer if it has already fired.
+    fn wake_at_deadline(&mut self, waker: &Waker) {
+        if let Some(ref timer_waker) = self.timer_waker {
+            let mut timer_waker = timer_waker.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(ref mut timer_waker) = *timer_waker {
+                if !timer_waker.will_wake(waker) {
+                    *timer_waker = waker.clone();
+                }
+                return;
+            }
+        }
+        let timer_waker = Arc::new(Mutex::new(Some(waker.clone())));
+        let remaining = self.deadline.saturating_duration_since(self.mock.now());
+        let thread_waker = timer_waker.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(remaining);
+            if let Some(waker) = thread_waker.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                waker.wake();
+            }
+        });
+        self.timer_waker = Some(timer_waker);
+    }
+}
+
+impl<'m> Future for VerifyEventually<'m> {
+    type Output = Result<(), VerificationError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let report = self.mock.verification_report();
+        if report.is_satisfied() {
+            return Poll::Ready(Ok(()));
+        }
+        if self.mock.now() >= self.deadline {
+            return Poll::Ready(Err(VerificationError::timeout(self.timeout, report.failures().to_vec())));
+        }
+        self.mock.wake_on_next_match(cx.waker());
+        self.wake_at_deadline(cx.waker());
+        Poll::Pending
+    }
+}
+
+#[cfg(all(test, not(feature = "disabled")))]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use clock::ManualClock;
+    use {ExpectBehaviour, MockControl};
+
+    #[test]
+    fn times_out_within_tolerance() {
+        let mut mock = MockState::new();
+        mock.should_verify_on_drop(false);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+        let timeout = Duration::from_millis(100);
+        let started = Instant::now();
+        let mut future = Box::pin(mock.verify_eventually(timeout));
+        let mut cx = Context::from_waker(Waker::noop());
+        let result = loop {
+            if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+                break result;
+            }
+            assert!(started.elapsed() < timeout + Duration::from_millis(200), "the verification did not time out");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert!(result.unwrap_err().is_timeout());
+        assert!(started.elapsed() >= timeout);
+    }
+
+    #[test]
+    fn times_out_after_manual_clock_advanced() {
+        let mut mock = MockState::new();
+        mock.should_verify_on_drop(false);
+        let clock = ManualClock::new();
+        mock.set_clock(Box::new(clock.clone()));
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+        let timeout = Duration::from_millis(50);
+        let woken = Arc::new(WakeFlag(AtomicBool::new(false)));
+        let waker = Waker::from(woken.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(mock.verify_eventually(timeout));
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        std::thread::sleep(timeout * 2);
+        assert!(woken.0.swap(false, Ordering::SeqCst));
+        assert!(future.as_mut().poll(&mut cx).is_pending(), "the manual clock has not reached the deadline");
+        clock.advance(timeout);
+        let started = Instant::now();
+        while !woken.0.load(Ordering::SeqCst) {
+            assert!(started.elapsed() < timeout + Duration::from_millis(200), "the re-armed timer did not fire");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(result) => assert!(result.unwrap_err().is_timeout()),
+            Poll::Pending => panic!("the verification did not time out"),
+        }
+    }
+
+    struct WakeFlag(AtomicBool);
+
+    impl std::task::Wake for WakeFlag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A fluent interface for defining *expected* behaviours with count and ordering constraints in a single expression.
+//!
+//! ```ignore
+//! mock.expect("Repository").method("save").at_least(1).before("commit").register()?;
+//! ```
+
+use std::fmt;
+
+use {ExpectBehaviour, ExpectBehaviourBuilder, InvalidCountRange, MockControl, MockState, OrderingConstraint};
+
+/// The definition of an *expected* behaviour in progress, created by `MockState::expect()`.
+pub struct FluentExpectation<'m> {
+    mock: &'m MockState,
+    requested_trait: &'static str,
+    method: Option<&'static str>,
+    builder: ExpectBehaviourBuilder,
+    ordering: Vec<OrderingConstraint>,
+}
+
+impl<'m> FluentExpectation<'m> {
+    pub(crate) fn new(mock: &'m MockState, requested_trait: &'static str) -> Self {
+        FluentExpectation {
+            mock,
+            requested_trait,
+            method: None,
+            builder: ExpectBehaviourBuilder::new(),
+            ordering: Vec::new(),
+        }
+    }
+
+    /// Sets the mocked method the behaviour is defined for.
+    pub fn method(mut self, method: &'static str) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Sets the unique id of the behaviour within the mocked method to which it belongs.
+    pub fn stmt_id(mut self, stmt_id: usize) -> Self {
+        self.builder = self.builder.stmt_id(stmt_id);
+        self
+    }
+
+    /// Sets the string representation of the behaviour's definition.
+    pub fn repr(mut self, stmt_repr: &str) -> Self {
+        self.builder = self.builder.repr(stmt_repr);
+        self
+    }
+
+    /// Sets the minimum number of matches.
+    pub fn at_least(mut self, times: usize) -> Self {
+        self.builder = self.builder.at_least(times);
+        self
+    }
+
+    /// Sets the maximum number of matches.
+    pub fn at_most(mut self, times: usize) -> Self {
+        self.builder = self.builder.at_most(times);
+        self
+    }
+
+    /// Sets the exact number of matches.
+    pub fn times(mut self, times: usize) -> Self {
+        self.builder = self.builder.times(times);
+        self
+    }
+
+    /// Sets the user-defined name by which other behaviours can reference the behaviour.
+    pub fn label(mut self, label: &str) -> Self {
+        self.builder = self.builder.label(label);
+        self
+    }
+
+    /// Requires all matches to occur before the first match of the behaviour with the `label`.
+    pub fn before(mut self, label: &str) -> Self {
+        self.ordering.push(OrderingConstraint::Before(label.to_string()));
+        self
+    }
+
+    /// Requires all matches to occur after the last match of the behaviour with the `label`.
+    pub fn after(mut self, label: &str) -> Self {
+        self.ordering.push(OrderingConstraint::After(label.to_string()));
+        self
+    }
+
+    /// Creates the behaviour without registering it.
+    ///
+    /// Fails if the count range is empty or if an ordering constraint references a label
+    /// which no expected behaviour of the mock carries.
+    pub fn build(self) -> Result<ExpectBehaviour, FluentExpectationError> {
+        for constraint in self.ordering.iter() {
+            let is_known = self.mock.expect_behaviours
+                                    .borrow()
+                                    .values()
+                                    .flatten()
+                                    .any(|b| b.label() == Some(constraint.label()));
+            if !is_known {
+                return Err(FluentExpectationError::UnknownLabel(constraint.label().to_string()));
+            }
+        }
+        let mut behaviour = self.builder.build().map_err(FluentExpectationError::InvalidCountRange)?;
+        for constraint in self.ordering {
+            behaviour = behaviour.with_ordering(constraint);
+        }
+        Ok(behaviour)
+    }
+
+    /// Creates the behaviour and registers it with the mock.
+    ///
+    /// Fails like `build()` and if no method has been set.
+    pub fn register(self) -> Result<(), FluentExpectationError> {
+        let mock = self.mock;
+        let requested_trait = self.requested_trait;
+        let method = self.method.ok_or(FluentExpectationError::MissingMethod)?;
+        let behaviour = self.build()?;
+        mock.add_expect_behaviour(requested_trait, method, behaviour);
+        Ok(())
+    }
+}
+
+/// The error returned if a `FluentExpectation` cannot be turned into a behaviour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FluentExpectationError {
+    /// The minimum number of matches exceeds the maximum.
+    InvalidCountRange(InvalidCountRange),
+    /// An ordering constraint references a label which no expected behaviour carries.
+    UnknownLabel(String),
+    /// The behaviour is registered without a method.
+    MissingMethod,
+}
+
+impl fmt::Display for FluentExpectationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FluentExpectationError::InvalidCountRange(ref error) => error.fmt(f),
+            FluentExpectationError::UnknownLabel(ref label) => {
+                write!(f, "Ordering constraint references the unknown label `{}`", label)
+            }
+            FluentExpectationError::MissingMethod => write!(f, "Expected behaviour defined without a method"),
+        }
+    }
+}
+
+impl std::error::Error for FluentExpectationError {}
+
+/// An assertion on the recorded number of calls of a method, created by `MockState::calls_of()`.
+///
+/// ```ignore
+/// mock.calls_of(("Animal", "legs")).to_be_called_times(2).assert();
+/// ```
+pub struct CallCountAssertion<'m> {
+    mock: &'m MockState,
+    requested_trait: &'static str,
+    method: &'static str,
+    at_least_times: usize,
+    at_most_times: Option<usize>,
+}
+
+impl<'m> CallCountAssertion<'m> {
+    pub(crate) fn new(mock: &'m MockState, requested_trait: &'static str, method: &'static str) -> Self {
+        CallCountAssertion {
+            mock,
+            requested_trait,
+            method,
+            at_least_times: 0,
+            at_most_times: None,
+        }
+    }
+
+    /// Requires the method to be called exactly `times`.
+    pub fn to_be_called_times(mut self, times: usize) -> Self {
+        self.at_least_times = times;
+        self.at_most_times = Some(times);
+        self
+    }
+
+    /// Requires the method to be called at least `times`.
+    pub fn to_be_called_at_least(mut self, times: usize) -> Self {
+        self.at_least_times = times;
+        self
+    }
+
+    /// Requires the method to be called at most `times`.
+    pub fn to_be_called_at_most(mut self, times: usize) -> Self {
+        self.at_most_times = Some(times);
+        self
+    }
+
+    /// Requires the method to be never called.
+    pub fn to_be_never_called(self) -> Self {
+        self.to_be_called_times(0)
+    }
+
+    /// Checks the recorded calls against the requirements.
+    ///
+    /// The calls are the summed matches of the method's *given* and *expected* behaviours.
+    pub fn check(&self) -> Result<(), String> {
+        let calls = self.mock.num_matches_of((self.requested_trait, self.method));
+        if self.at_least_times <= calls && self.at_most_times.is_none_or(|at_most_times| calls <= at_most_times) {
+            return Ok(());
+        }
+        let expected = match self.at_most_times {
+            Some(at_most_times) if at_most_times == self.at_least_times => format!("exactly {}", at_most_times),
+            Some(at_most_times) => format!("between {} and {}", self.at_least_times, at_most_times),
+            None => format!("at least {}", self.at_least_times),
+        };
+        Err(format!("{}::{} has been called {} times, expected {} times",
+                    self.requested_trait,
+                    self.method,
+                    calls,
+                    expected))
+    }
+
+    /// Panics with the message of `check()` if the requirements are not met.
+    pub fn assert(&self) {
+        if let Err(message) = self.check() {
+            panic!("{}", message);
+        }
+    }
+}
@@ -16,11 +16,46 @@
 //! The support library for **[galvanic-mock](https://www.github.com/mindsbackyard/galvanic-mock)**.
 //!
 //! The crate provides common traits for all mocks generated by **galvanic-mock** as well as data structures for handling the state of mock objects.
+//!
+//! With the `disabled` feature the public API stays the same but all tracking is turned into no-ops:
+//! behaviours are neither registered nor matched, no calls are recorded, and every verification passes trivially.
+//! This guards builds which pull in the crate accidentally against the runtime cost of the mocks.
+//!
+//! The crate requires `std`, a `no_std` build is not supported. Besides `HashMap` and the `StderrSink`,
+//! the mock state relies on `std::thread` for panic detection on drop,
+//! and on `std::time` for the `timing` and `async` features and the clocks.
+//! A `core`/`alloc` subset would have to replace these by `BTreeMap`s and a caller-provided report mechanism.
 
 #[cfg(feature = "galvanic_assert_integration")] extern crate galvanic_assert;
+#[cfg(feature = "log_integration")] extern crate log;
+#[cfg(feature = "regex")] extern crate regex;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(feature = "serde")] extern crate serde_json;
+
+use std::collections::{HashMap, VecDeque};
+use std::cell::{Ref, RefCell};
+
+pub mod builder;
+pub mod clock;
+mod constraints;
+#[cfg(feature = "async")] pub mod eventually;
+pub mod fluent;
+pub mod matchers;
+pub mod report;
+pub mod sink;
+pub mod snapshot;
+pub mod stats;
+pub mod template;
 
-use std::collections::HashMap;
-use std::cell::RefCell;
+pub use builder::MockBuilder;
+pub use clock::{Clock, ManualClock, SequenceClock, SystemClock};
+pub use fluent::{CallCountAssertion, FluentExpectation, FluentExpectationError};
+pub use report::{VerificationError, VerificationReport};
+pub use sink::{MessageSink, StderrSink};
+#[cfg(feature = "log_integration")] pub use sink::LogSink;
+pub use snapshot::{CountSnapshot, MockStateSnapshot, SnapshotDelta};
+pub use stats::AggregateStats;
+pub use template::MockTemplate;
 
 /// A trait for controlling the behaviour of a mock.
 ///
@@ -66,6 +101,8 @@ pub trait MockControl {
     fn reset_expected_behaviours(&mut self);
 
     /// Returns `true` iff all expected interactions with the mock have occurred.
+    ///
+    /// If the mock enforces the use of all *given* behaviours then unused ones are reported as well.
     fn are_expected_behaviours_satisfied(&self) -> bool;
 
     /// Panics if some expected interaction with the mock has not occurred.
@@ -76,10 +113,52 @@ pub trait MockControl {
     ///
     /// Verification should be skipped if the current thread is already panicking.
     /// This method should also be executed once the implementor is drpped if verication on drop is enabled.
+    /// It is safe to call the method several times, a successful verification is not repeated when the mock is dropped.
     fn verify(&self);
+
+    /// Returns a single line summarizing the interactions with the mock.
+    ///
+    /// For each mocked trait's method the number of matching invocations is listed.
+    /// Methods with expected behaviours are annotated with their expected repetitions and whether they are satisfied.
+    /// The summary is meant for logging, e.g., at the end of test, regardless of the verification's outcome.
+    fn interaction_summary(&self) -> String;
+
+    /// Returns a description of the behaviours configured for the mock, one line per method and behaviour.
+    ///
+    /// For each mocked trait's method the number of *given* and *expected* behaviours is listed,
+    /// followed by the behaviours' descriptions in registration order. The methods are sorted by trait and name.
+    /// Unlike a verification report this renders the setup, e.g., to log the arrange phase at the start of a test.
+    fn describe_configuration(&self) -> String;
+
+    /// Returns the sum of the matches of all *given* and *expected* behaviours of the mock.
+    ///
+    /// As a single call may match a given as well as an expected behaviour this is a coarse metric of how often the mock has been used.
+    fn total_calls(&self) -> usize;
+
+    /// Returns the sum of the matches of all *given* and *expected* behaviours of `requested_trait`'s methods.
+    ///
+    /// See `total_calls()`.
+    fn total_calls_for_trait(&self, requested_trait: &'static str) -> usize;
+
+    /// Returns the matches of the mock's behaviours in the order of their occurrence.
+    fn invocation_timeline(&self) -> Vec<InvocationRecord>;
+
+    /// For *internal* use only.
+    ///
+    /// Counts a call of a trait's method regardless of the behaviours registered for it.
+    /// The generated dispatch code should count every call, the counts are read by `MockState::invocation_count()`.
+    fn count_call(&self, requested_trait: &'static str, method: &'static str);
 }
 
 
+/// The method name under which behaviours applying to any method of a trait are registered.
+///
+/// See `MockState::add_given_behaviour_wildcard()`.
+pub const ANY_METHOD: &str = "*";
+
+/// The key under which behaviours spanning several methods are registered, see `ExpectBehaviour::spanning()`.
+const SPANNING_KEY: (&str, &str) = (ANY_METHOD, ANY_METHOD);
+
 /// Stores the state of a mock.
 ///
 /// The state of a mock object is compromised by its enabled *given* and *expected* behaviours.
@@ -91,283 +170,4582 @@ pub struct MockState {
     pub expect_behaviours: RefCell<HashMap<(&'static str, &'static str), Vec<ExpectBehaviour>>>,
     /// Whether the *expected behaviours should be verfied on drop.
     verify_on_drop: bool,
+    /// Whether *given* behaviours which have never been matched should fail the verification.
+    verify_no_unused_given: bool,
+    /// Whether the mock has been verified successfully.
+    verified: std::cell::Cell<bool>,
+    /// Whether calls to the mock should be recorded in the `call_log`.
+    log_calls: bool,
+    /// The calls received by the mock in the order of their occurrence.
+    call_log: RefCell<VecDeque<CallLogEntry>>,
+    /// Whether exceeding the maximum of an expected behaviour merely produces a warning.
+    treat_at_most_as_nonfatal: bool,
+    /// Receives the diagnostic messages of the mock.
+    message_sink: Box<dyn MessageSink>,
+    /// Groups of *expected* behaviours which are verified as a whole.
+    expect_groups: RefCell<Vec<ExpectGroup>>,
+    /// The state shared with all registered behaviours, allocated on first use.
+    shared: std::cell::OnceCell<std::rc::Rc<SharedState>>,
+    /// How a *given* behaviour is selected if several match a call.
+    selection_strategy: SelectionStrategy,
+    /// Whether the verification warns about methods with behaviours of which none has been matched.
+    warn_on_fully_unused_methods: bool,
+    /// The state of the random number generator used by `SelectionStrategy::WeightedRandom`.
+    rng_state: std::cell::Cell<u64>,
+    /// The trait/method keys behaviours may be registered for, any key if `None`.
+    known_methods: Option<std::collections::HashSet<(&'static str, &'static str)>>,
+    /// Invoked with the report of a failed verification before `verify()` panics.
+    on_verification_failure: RefCell<Option<VerificationFailureCallback>>,
+    /// The calls which matched no behaviour in the order of their occurrence.
+    unexpected_calls: RefCell<Vec<UnexpectedCall>>,
+    /// Whether `add_expect_behaviour()` merges behaviours with the `stmt_id` of an already registered one.
+    deduplicate_expectations: bool,
+    /// The default matchers for arguments of a type, each a `Box<OwnedArgMatcher<T>>` keyed by the `TypeId` of `T`.
+    type_matchers: HashMap<std::any::TypeId, std::rc::Rc<dyn std::any::Any>>,
+    /// The real implementation calls are delegated to if the mock acts as a spy.
+    spy_delegate: Option<std::rc::Rc<dyn std::any::Any>>,
+    /// Auxiliary values attached to the mock by the user, keyed by their type.
+    user_data: RefCell<HashMap<std::any::TypeId, Box<dyn std::any::Any>>>,
+    /// Whether `verify()` sends the report's messages to the message sink.
+    print_on_verify: bool,
+    /// Whether `verify()` panics if the verification fails.
+    panic_on_verify: bool,
+    /// The report of the most recent `verify()`.
+    last_report: RefCell<Option<VerificationReport>>,
+    /// Whether adding behaviours panics, see `freeze()`.
+    frozen: bool,
+    /// Whether dropping a mock with *expected* behaviours panics unless it has been verified explicitly.
+    require_explicit_verify: bool,
+    /// Whether `verify()`, `try_verify()`, or `assert_fully_satisfied()` has been called explicitly, i.e., not from `drop()`.
+    explicitly_verified: std::cell::Cell<bool>,
+    /// Selects the *expected* behaviours whose messages are reported, see `set_report_filter()`.
+    report_filter: Option<ReportFilter>,
+    /// The order of the messages of the *expected* behaviours in a verification report.
+    report_ordering: ReportOrdering,
+    /// Whether the mock is being verified, adding behaviours panics meanwhile.
+    verifying: std::cell::Cell<bool>,
+    /// The number of matches of each trait's method when `checkpoint()` has been called.
+    checkpoint: RefCell<HashMap<(&'static str, &'static str), usize>>,
+    /// The transformations applied to the arguments of a trait's method before matching, see `set_arg_transform()`.
+    arg_transforms: HashMap<(&'static str, &'static str), ArgTransform>,
+    /// How *expected* behaviours count a call matched by several of them, see `set_expect_match_mode()`.
+    expect_match_mode: ExpectMatchMode,
+    /// The match modes overriding `expect_match_mode` for a trait's method.
+    expect_match_modes: HashMap<(&'static str, &'static str), ExpectMatchMode>,
+    /// Alternative names of *expected* behaviours mapped to their trait, method, and `stmt_id`, see `alias()`.
+    aliases: RefCell<HashMap<String, (&'static str, &'static str, usize)>>,
+    /// The maximum number of failure messages sent to the message sink, see `set_max_reported_failures()`.
+    max_reported_failures: Option<usize>,
+    /// The trait/method keys whose *expected* behaviours must be satisfied in registration order.
+    in_order_methods: std::collections::HashSet<(&'static str, &'static str)>,
 }
 
-impl MockState {
-    pub fn new() -> Self {
-        Self {
-            given_behaviours: RefCell::new(HashMap::new()),
-            expect_behaviours: RefCell::new(HashMap::new()),
-            verify_on_drop: true,
-        }
-    }
+/// Resets the verification flag of a mock once its verification ends, even if it panics.
+struct VerifyingGuard<'a> {
+    verifying: &'a std::cell::Cell<bool>,
+    previous: bool,
 }
 
-impl MockControl for MockState {
-    fn should_verify_on_drop(&mut self, flag: bool) {
-        self.verify_on_drop = flag;
+impl<'a> Drop for VerifyingGuard<'a> {
+    fn drop(&mut self) {
+        self.verifying.set(self.previous);
     }
+}
 
-    fn add_given_behaviour(&self,
-                           requested_trait: &'static str,
-                           method: &'static str,
-                           behaviour: GivenBehaviour) {
-        self.given_behaviours
-            .borrow_mut()
-            .entry((requested_trait, method))
-            .or_insert_with(|| Vec::new())
-            .push(behaviour);
-    }
+/// A callback receiving the report of a failed verification.
+type VerificationFailureCallback = Box<dyn FnMut(&VerificationReport)>;
 
-    fn reset_given_behaviours(&mut self) {
-        self.given_behaviours.borrow_mut().clear();
-    }
+/// Selects the *expected* behaviours whose messages are included in a verification report.
+type ReportFilter = Box<dyn Fn(&ExpectBehaviour) -> bool>;
 
-    fn add_expect_behaviour(&self,
-                            requested_trait: &'static str,
-                            method: &'static str,
-                            behaviour: ExpectBehaviour) {
-        self.expect_behaviours
-            .borrow_mut()
-            .entry((requested_trait, method))
-            .or_insert_with(|| Vec::new())
-            .push(behaviour);
-    }
+/// Determines which *given* behaviour is selected if several of them match a call.
+///
+/// See `MockState::select_given()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// Selects the first matching behaviour in registration order.
+    #[default]
+    FirstMatch,
+    /// Selects the matching behaviour which has been matched most recently.
+    ///
+    /// Falls back to `FirstMatch` if none of the matching behaviours has been matched before.
+    LastMatch,
+    /// Selects the last matching behaviour in registration order, i.e., later definitions override earlier ones.
+    MostRecentlyAdded,
+    /// Selects the matching behaviour with the fewest matches, ties are broken by registration order.
+    ///
+    /// This distributes calls between the matching behaviours round-robin.
+    LeastUsed,
+    /// Selects a matching behaviour at random with a probability proportional to its weight.
+    ///
+    /// The random numbers are generated from the given seed, i.e., the same seed reproduces the same selections.
+    /// Behaviours with weight `0` are never selected. See `GivenBehaviour::with_weight()`.
+    WeightedRandom(u64),
+}
 
-    fn reset_expected_behaviours(&mut self) {
-        self.expect_behaviours.borrow_mut().clear();
+/// Determines which *expected* behaviours count a call if several of them match it.
+///
+/// See `MockState::match_expected()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpectMatchMode {
+    /// Every matching behaviour counts the call.
+    #[default]
+    AllMatching,
+    /// Only the first matching behaviour in registration order counts the call.
+    ///
+    /// Overlapping count ranges no longer add up, e.g., a second `with_at_least(1, ..)` behaviour
+    /// accepting the same arguments is never matched while the first one is enabled.
+    FirstMatching,
+}
+
+/// Determines the order of the messages of *expected* behaviours in a verification report.
+///
+/// See `MockState::set_report_ordering()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportOrdering {
+    /// Lists the behaviours by trait and method, and in registration order within a method.
+    #[default]
+    RegistrationOrder,
+    /// Lists over-saturated behaviours, which are definitely matched too often, before unsatisfied ones.
+    BySeverity,
+    /// Lists the behaviours by the number of matches they are missing or exceed, the largest distance first.
+    ByDistanceFromTarget,
+}
+
+/// A summary of the state of a mock's expectations, see `MockState::health()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockHealth {
+    /// All *expected* behaviours are satisfied and no unexpected call has been recorded.
+    AllSatisfied,
+    /// The number of *expected* behaviours which are unsatisfied without exceeding their maximum.
+    SomeUnderSaturated(usize),
+    /// The number of *expected* behaviours which have been matched more often than their maximum.
+    SomeOverSaturated(usize),
+    /// Some *expected* behaviours are unsatisfied and others exceeded their maximum.
+    Mixed {
+        /// The number of unsatisfied behaviours not exceeding their maximum.
+        under: usize,
+        /// The number of behaviours exceeding their maximum.
+        over: usize,
+    },
+    /// The number of calls which matched no behaviour, see `MockState::record_unexpected_call()`.
+    HadUnexpectedCalls(usize),
+}
+
+/// Advances the state of a *SplitMix64* generator and returns the next random number.
+fn next_random(state: &std::cell::Cell<u64>) -> u64 {
+    let next = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    state.set(next);
+    let mut z = next;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// The part of a mock's state which is shared with the behaviours registered with the mock.
+struct SharedState {
+    /// Whether matches of the behaviours are recorded.
+    recording: std::cell::Cell<bool>,
+    /// The matches of the behaviours in the order of their occurrence.
+    timeline: RefCell<VecDeque<InvocationRecord>>,
+    /// The maximum number of entries kept in the timeline and the call log, see `MockState::set_max_recorded_calls()`.
+    max_recorded_calls: std::cell::Cell<Option<usize>>,
+    /// Whether old entries have been dropped from the timeline or the call log.
+    records_truncated: std::cell::Cell<bool>,
+    /// The number of calls of each trait's method recorded by `MockState::record_call()`.
+    call_counts: RefCell<HashMap<(&'static str, &'static str), usize>>,
+    /// The phase whose *expected* behaviours are active, see `MockState::advance_phase()`.
+    current_phase: std::cell::Cell<u32>,
+    /// The length of the timeline when `MockState::begin_act()` has been called, if it has been called.
+    act_position: std::cell::Cell<Option<usize>>,
+    /// The tasks awaiting the next match, woken by `Registration::record_match()`.
+    #[cfg(feature = "async")]
+    wakers: RefCell<Vec<std::task::Waker>>,
+    /// The source of all time readings of the mock and its behaviours.
+    clock: RefCell<std::rc::Rc<dyn Clock>>,
+    /// The source of the sequence numbers of the matches if the mock is attached to one.
+    sequence_clock: RefCell<Option<SequenceClock>>,
+    /// The mock's private source of the sequence numbers of the matches while it is not attached to a `SequenceClock`.
+    next_sequence_number: std::cell::Cell<usize>,
+    /// The statistics additionally counting the matches, see `MockState::fork_with_shared_stats()`.
+    aggregate_stats: RefCell<Option<std::rc::Rc<RefCell<AggregateStats>>>>,
+}
+
+impl SharedState {
+    fn new() -> Self {
+        Self {
+            recording: std::cell::Cell::new(true),
+            timeline: RefCell::new(VecDeque::new()),
+            max_recorded_calls: std::cell::Cell::new(None),
+            records_truncated: std::cell::Cell::new(false),
+            call_counts: RefCell::new(HashMap::new()),
+            current_phase: std::cell::Cell::new(0),
+            act_position: std::cell::Cell::new(None),
+            #[cfg(feature = "async")]
+            wakers: RefCell::new(Vec::new()),
+            clock: RefCell::new(std::rc::Rc::new(SystemClock)),
+            sequence_clock: RefCell::new(None),
+            next_sequence_number: std::cell::Cell::new(0),
+            aggregate_stats: RefCell::new(None),
+        }
     }
 
-    fn are_expected_behaviours_satisfied(&self) -> bool {
-        let mut unsatisfied_messages: Vec<String> = Vec::new();
-        for behaviour in self.expect_behaviours.borrow().values().flat_map(|vs| vs) {
-            if !behaviour.is_saturated() {
-                unsatisfied_messages
-                    .push(format!("Behaviour unsatisfied with {} matching invocations: {}",
-                                  behaviour.num_matches.get(),
-                                  behaviour.describe()));
+    /// Appends the `record` to the timeline or the call log, dropping the oldest entry if the limit is reached.
+    fn push_bounded<T>(&self, records: &mut VecDeque<T>, record: T) {
+        if let Some(limit) = self.max_recorded_calls.get() {
+            while records.len() >= limit && records.pop_front().is_some() {
+                self.records_truncated.set(true);
             }
-        }
-        if !unsatisfied_messages.is_empty() {
-            for message in unsatisfied_messages {
-                eprintln!("{}", message);
+            if limit == 0 {
+                self.records_truncated.set(true);
+                return;
             }
-            false
-        } else {
-            true
         }
+        records.push_back(record);
     }
 
-    fn verify(&self) {
-        if !std::thread::panicking() && !self.are_expected_behaviours_satisfied() {
-            panic!("There are unsatisfied expected behaviours for mocked traits.");
-        }
+    /// Returns the current time of the mock's clock.
+    #[cfg(any(feature = "timing", feature = "async"))]
+    fn now(&self) -> std::time::Instant {
+        let clock = self.clock.borrow().clone();
+        clock.now()
     }
 }
 
-impl std::ops::Drop for MockState {
-    /// Verfies the *expected interactions* on the mock if the policy is enabled.
+/// Links a behaviour to the mock it is registered with.
+struct Registration {
+    /// The state shared with the mock.
+    shared: std::rc::Rc<SharedState>,
+    /// The name of the mocked trait the behaviour is registered for.
+    requested_trait: &'static str,
+    /// The name of the mocked method the behaviour is registered for.
+    method: &'static str,
+    /// When the behaviour has been registered.
+    #[cfg(feature = "timing")]
+    registered_at: std::time::Instant,
+}
+
+impl Registration {
+    /// Returns the number of calls recorded for the method the behaviour is registered for.
+    fn call_count(&self) -> usize {
+        self.shared.call_counts.borrow().get(&(self.requested_trait, self.method)).cloned().unwrap_or(0)
+    }
+
+    /// Appends a match of the behaviour with `stmt_id` to the mock's timeline.
     ///
-    /// # Panics
-    /// iff the verification fails.
-    fn drop(&mut self) {
-        if self.verify_on_drop {
-            self.verify();
+    /// The match is counted by the mock's aggregate statistics, if any.
+    /// Tasks awaiting `MockState::verify_eventually()` are woken.
+    fn record_match(&self, stmt_id: usize, kind: BehaviourKind) -> usize {
+        let sequence_number = match *self.shared.sequence_clock.borrow() {
+            Some(ref sequence_clock) => sequence_clock.tick(),
+            None => {
+                let sequence_number = self.shared.next_sequence_number.get();
+                self.shared.next_sequence_number.set(sequence_number + 1);
+                sequence_number
+            }
+        };
+        self.shared.push_bounded(&mut self.shared.timeline.borrow_mut(),
+                                 InvocationRecord {
+                                     sequence_number,
+                                     requested_trait: self.requested_trait,
+                                     method: self.method,
+                                     stmt_id,
+                                     kind,
+                                 });
+        if let Some(ref stats) = *self.shared.aggregate_stats.borrow() {
+            stats.borrow_mut().record_match(self.requested_trait, self.method);
+        }
+        #[cfg(feature = "async")]
+        for waker in self.shared.wakers.borrow_mut().drain(..) {
+            waker.wake();
         }
+        sequence_number
     }
 }
 
-
-/// Defines a matcher for the arguments of a mocked method.
+/// Returns `true` iff a behaviour with the `registration` should record its matches.
 ///
-/// A matcher may check a single argument of a method or all arguments at once.
-/// If all arguments are to be checked then they should be passed in a curried form, e.g., as a tuple.
-pub trait ArgMatcher<'a, T: 'a> {
-    // Returns `true` iff the `actual` arguments satisfy the matcher.
-    fn match_args(&self, actual: &'a T) -> bool;
+/// Behaviours which are not registered with a mock always record their matches.
+fn is_recording(registration: &Option<Registration>) -> bool {
+    registration.as_ref().is_none_or(|registration| registration.shared.recording.get())
 }
 
-/// Any function accepting an argument and returning a `bool` can be used as `ArgMatcher`.
-impl<'a, T: 'a, F> ArgMatcher<'a, T> for F
-    where F: Fn(&'a T) -> bool
-{
-    fn match_args(&self, actual: &'a T) -> bool {
-        self(actual)
+impl MockState {
+    /// Creates a new mock state without behaviours.
+    ///
+    /// The construction does not allocate, the behaviour maps and the state shared with the behaviours
+    /// are allocated once the first behaviour is added or a call is recorded.
+    /// A `const` constructor is not feasible as the maps' `RandomState` hashers are initialized at runtime.
+    pub fn new() -> Self {
+        Self {
+            given_behaviours: RefCell::new(HashMap::new()),
+            expect_behaviours: RefCell::new(HashMap::new()),
+            verify_on_drop: true,
+            verify_no_unused_given: false,
+            verified: std::cell::Cell::new(false),
+            log_calls: false,
+            call_log: RefCell::new(VecDeque::new()),
+            treat_at_most_as_nonfatal: false,
+            message_sink: sink::default_sink(),
+            expect_groups: RefCell::new(Vec::new()),
+            shared: std::cell::OnceCell::new(),
+            selection_strategy: SelectionStrategy::default(),
+            warn_on_fully_unused_methods: false,
+            rng_state: std::cell::Cell::new(0),
+            known_methods: None,
+            on_verification_failure: RefCell::new(None),
+            unexpected_calls: RefCell::new(Vec::new()),
+            deduplicate_expectations: false,
+            type_matchers: HashMap::new(),
+            spy_delegate: None,
+            user_data: RefCell::new(HashMap::new()),
+            print_on_verify: true,
+            panic_on_verify: true,
+            last_report: RefCell::new(None),
+            frozen: false,
+            require_explicit_verify: false,
+            explicitly_verified: std::cell::Cell::new(false),
+            report_filter: None,
+            report_ordering: ReportOrdering::default(),
+            verifying: std::cell::Cell::new(false),
+            checkpoint: RefCell::new(HashMap::new()),
+            arg_transforms: HashMap::new(),
+            expect_match_mode: ExpectMatchMode::default(),
+            expect_match_modes: HashMap::new(),
+            aliases: RefCell::new(HashMap::new()),
+            max_reported_failures: None,
+            in_order_methods: std::collections::HashSet::new(),
+        }
     }
-}
 
-/// All matchers of the **galvanic-assert** crate can be used as `ArgMatcher`.
-///
-/// The crate's matchers can either be used to inspect a single argument or all of them (in curried form).
-#[cfg(feature = "galvanic_assert_integration")]
-impl<'a, T: 'a> ArgMatcher<'a, T> for Box<::galvanic_assert::Matcher<'a, T> + 'a> {
-    fn match_args(&self, actual: &'a T) -> bool {
-        self.check(actual).into()
+    /// Returns the state shared with the registered behaviours, allocating it on first use.
+    fn shared(&self) -> &std::rc::Rc<SharedState> {
+        self.shared.get_or_init(|| std::rc::Rc::new(SharedState::new()))
     }
-}
-
-/// Stores the state of a *given* behaviour.
-pub struct GivenBehaviour {
-    /// The unique id of the behaviour within the mocked method to which it belongs.
-    pub stmt_id: usize,
-    /// How often the behaviour has been matched.
-    num_matches: std::cell::Cell<usize>,
-    /// How often the behaviour should be matched before it is exhausted, `None` if never.
-    expected_matches: Option<usize>,
-    /// The bound variables available to the behaviour's `ArgMatcher`.
-    pub bound: std::rc::Rc<std::any::Any>,
-    /// A string representation of the behaviour's definition.
-    stmt_repr: String,
-}
 
-impl GivenBehaviour {
-    /// Creates a new behaviour which is never exhausted.
-    pub fn with(stmt_id: usize, bound: std::rc::Rc<std::any::Any>, stmt_repr: &str) -> Self {
-        Self {
-            stmt_id: stmt_id,
-            num_matches: std::cell::Cell::new(0),
-            expected_matches: None,
-            bound: bound,
-            stmt_repr: stmt_repr.to_string(),
+    /// Links a behaviour which is being registered for the trait's method to the mock.
+    fn registration_for(&self, requested_trait: &'static str, method: &'static str) -> Registration {
+        Registration {
+            shared: self.shared().clone(),
+            requested_trait,
+            method,
+            #[cfg(feature = "timing")]
+            registered_at: self.shared().now(),
         }
     }
 
-    /// Creates a new behaviour which is never exhausted after being matched `times`.
-    pub fn with_times(times: usize,
-                      stmt_id: usize,
-                      bound: std::rc::Rc<std::any::Any>,
-                      stmt_repr: &str)
-                      -> Self {
-        Self {
-            stmt_id: stmt_id,
-            num_matches: std::cell::Cell::new(0),
-            expected_matches: Some(times),
-            bound: bound,
-            stmt_repr: stmt_repr.to_string(),
-        }
+    /// Creates a new mock state whose expected behaviours are not verified on drop.
+    ///
+    /// This is meant for throwaway mocks in exploratory tests, verification can still be enabled with `should_verify_on_drop()`.
+    pub fn new_unverified() -> Self {
+        let mut state = Self::new();
+        state.verify_on_drop = false;
+        state
     }
 
-    /// Notifies the behaviour that it has been matched.
-    pub fn matched(&self) {
-        self.num_matches.set(self.num_matches.get() + 1);
+    /// Passing `true` lets the verification fail if some *given* behaviour has never been matched.
+    ///
+    /// This detects stubs which are not needed by the code under test, also known as *strict stubbing*.
+    /// The policy is the mock's `strict_given` mode, there is no separate flag for it.
+    /// Each unused behaviour is reported with its `stmt_repr`, this includes the verification on drop.
+    /// Disabled behaviours are not reported.
+    /// The policy is disabled by default.
+    pub fn should_verify_no_unused_given(&mut self, flag: bool) {
+        self.verify_no_unused_given = flag;
     }
 
-    /// Returns `true` iff the behaviour is exhausted.
-    pub fn is_saturated(&self) -> bool {
-        match self.expected_matches {
-            Some(limit) => self.num_matches.get() >= limit,
-            None => false,
-        }
+    /// Returns `true` iff the verification fails for *given* behaviours which have never been matched.
+    pub fn is_verifying_no_unused_given(&self) -> bool {
+        self.verify_no_unused_given
     }
 
-    /// Returns a description of the behaviour.
-    pub fn describe(&self) -> &str {
-        &self.stmt_repr
+    /// Passing `false` disables recording the matches of all behaviours registered with the mock.
+    ///
+    /// While recording is disabled, `matched()` of the behaviours does not mutate any state.
+    /// This allows to measure the bare dispatch cost of the mock, e.g., in benchmarks of the code under test.
+    /// Verification is meaningless while recording is disabled as the matches are not counted.
+    /// Recording is enabled by default.
+    pub fn set_recording(&self, flag: bool) {
+        self.shared().recording.set(flag);
     }
-}
 
+    /// Returns `true` iff the matches of the mock's behaviours are recorded.
+    pub fn is_recording(&self) -> bool {
+        self.shared.get().is_none_or(|shared| shared.recording.get())
+    }
 
-/// Stores the state of a *expected* behaviour.
-pub struct ExpectBehaviour {
-    /// The unique id of the behaviour within the mocked method to which it belongs.
-    pub stmt_id: usize,
-    /// How often the behaviour has been matched.
-    num_matches: std::cell::Cell<usize>,
-    /// The expected minimum number of matches for the behaviour to be satisfied
-    expected_min_matches: Option<usize>,
-    /// The expected maximum number of matches for the behaviour to be satisfied
-    expected_max_matches: Option<usize>,
-    #[allow(dead_code)] in_order: Option<bool>,
-    /// The bound variables available to the behaviour's `ArgMatcher`.
-    pub bound: std::rc::Rc<std::any::Any>,
-    /// A string representation of the behaviour's definition.
-    stmt_repr: String,
-}
+    /// Passing `true` lets the verification only warn about expected behaviours exceeding their maximum number of matches.
+    ///
+    /// Behaviours which are matched less often than their minimum still fail the verification.
+    /// The saturation status of the behaviours is unaffected by the policy.
+    /// The policy is disabled by default.
+    pub fn should_treat_at_most_as_nonfatal(&mut self, flag: bool) {
+        self.treat_at_most_as_nonfatal = flag;
+    }
 
+    /// Passing `true` lets the verification warn about methods which are configured but have never been called.
+    ///
+    /// A method is reported if it has registered *given* or *expected* behaviours but none of them has ever been matched.
+    /// The warnings do not fail the verification.
+    /// The policy is disabled by default.
+    pub fn should_warn_on_fully_unused_methods(&mut self, flag: bool) {
+        self.warn_on_fully_unused_methods = flag;
+    }
 
-impl ExpectBehaviour {
-    /// Creates a new behaviour which is satisfied if matched `times`.
-    pub fn with_times(times: usize,
-                      stmt_id: usize,
-                      bound: std::rc::Rc<std::any::Any>,
-                      stmt_repr: &str)
-                      -> Self {
-        Self {
-            stmt_id: stmt_id,
-            num_matches: std::cell::Cell::new(0),
-            expected_min_matches: Some(times),
-            expected_max_matches: Some(times),
-            in_order: None,
-            bound: bound,
-            stmt_repr: stmt_repr.to_string(),
-        }
+    /// Passing `false` stops `verify()` from sending the verification's messages to the message sink.
+    ///
+    /// The policy is enabled by default.
+    pub fn should_print_on_verify(&mut self, flag: bool) {
+        self.print_on_verify = flag;
     }
 
-    /// Creates a new behaviour which is satisfied if matched `at_least_times`.
-    pub fn with_at_least(at_least_times: usize,
-                         stmt_id: usize,
-                         bound: std::rc::Rc<std::any::Any>,
-                         stmt_repr: &str)
-                         -> Self {
-        Self {
-            stmt_id: stmt_id,
-            num_matches: std::cell::Cell::new(0),
-            expected_min_matches: Some(at_least_times),
-            expected_max_matches: None,
-            in_order: None,
-            bound: bound,
-            stmt_repr: stmt_repr.to_string(),
-        }
+    /// Passing `false` stops `verify()` from panicking if the verification fails.
+    ///
+    /// If printing is disabled as well `verify()` silently stores its report, see `last_verification_report()`.
+    /// The policy is enabled by default.
+    pub fn should_panic_on_verify(&mut self, flag: bool) {
+        self.panic_on_verify = flag;
     }
 
-    /// Creates a new behaviour which is satisfied if matched `at_most_times`.
-    pub fn with_at_most(at_most_times: usize,
-                        stmt_id: usize,
-                        bound: std::rc::Rc<std::any::Any>,
-                        stmt_repr: &str)
-                        -> Self {
-        Self {
-            stmt_id: stmt_id,
-            num_matches: std::cell::Cell::new(0),
-            expected_min_matches: None,
-            expected_max_matches: Some(at_most_times),
-            in_order: None,
-            bound: bound,
-            stmt_repr: stmt_repr.to_string(),
-        }
+    /// Passing `true` lets dropping the mock panic if it has *expected* behaviours but has never been verified explicitly.
+    ///
+    /// This enforces explicit verification instead of relying on the verification on drop.
+    /// Any of `verify()`, `try_verify()`, and `assert_fully_satisfied()` counts as explicit verification.
+    /// The policy is disabled by default.
+    pub fn should_require_explicit_verify(&mut self, flag: bool) {
+        self.require_explicit_verify = flag;
     }
 
-    /// Creates a new behaviour which is satisfied if matched between `[at_least_times, at_most_times]` (inclusive endpoints).
-    pub fn with_between(at_least_times: usize,
-                        at_most_times: usize,
-                        stmt_id: usize,
-                        bound: std::rc::Rc<std::any::Any>,
-                        stmt_repr: &str)
-                        -> Self {
-        Self {
-            stmt_id: stmt_id,
-            num_matches: std::cell::Cell::new(0),
-            expected_min_matches: Some(at_least_times),
-            expected_max_matches: Some(at_most_times),
-            in_order: None,
-            bound: bound,
-            stmt_repr: stmt_repr.to_string(),
+    /// Returns the report of the most recent `verify()`, `None` if the mock has not been verified yet.
+    pub fn last_verification_report(&self) -> Option<VerificationReport> {
+        self.last_report.borrow().clone()
+    }
+
+    /// Sets the destination of the mock's diagnostic messages.
+    ///
+    /// By default all messages are printed to `stderr`, or forwarded to the **log** crate with the `log_integration` feature.
+    pub fn set_message_sink(&mut self, sink: Box<dyn MessageSink>) {
+        self.message_sink = sink;
+    }
+
+    /// Registers the default matcher for arguments of type `T`, replacing a previously registered one.
+    ///
+    /// The dispatch code may look the matcher up with `type_matcher()` if a behaviour defines no explicit matcher
+    /// for an argument of type `T`. An explicit matcher of a behaviour always takes precedence over the default.
+    pub fn register_type_matcher<T: 'static, M: OwnedArgMatcher<T>>(&mut self, matcher: M) {
+        let matcher: Box<dyn OwnedArgMatcher<T>> = Box::new(matcher);
+        self.type_matchers.insert(std::any::TypeId::of::<T>(), std::rc::Rc::new(matcher));
+    }
+
+    /// Returns the default matcher for arguments of type `T`, if any.
+    pub fn type_matcher<T: 'static>(&self) -> Option<&dyn OwnedArgMatcher<T>> {
+        self.type_matchers
+            .get(&std::any::TypeId::of::<T>())
+            .and_then(|matcher| matcher.downcast_ref::<Box<dyn OwnedArgMatcher<T>>>())
+            .map(|matcher| &**matcher)
+    }
+
+    /// Sets a transformation applied to the arguments of each call of the trait's method before they are matched.
+    ///
+    /// This normalizes arguments once instead of in every matcher, e.g., by lowercasing strings or rounding floats.
+    /// The transformation must return the arguments as the same type the method's matchers expect,
+    /// otherwise the matchers' downcasts fail. A transformation set before for the method is replaced.
+    pub fn set_arg_transform(&mut self, requested_trait: &'static str, method: &'static str, transform: ArgTransform) {
+        self.arg_transforms.insert((requested_trait, method), transform);
+    }
+
+    /// For *internal* use only.
+    ///
+    /// Applies the transformation set by `set_arg_transform()` for the trait's method to the call's `args`.
+    /// The dispatch code should pass the arguments through this before evaluating any matcher.
+    /// Returns the `args` unchanged if no transformation is set.
+    pub fn transform_args(&self,
+                          requested_trait: &'static str,
+                          method: &'static str,
+                          args: std::rc::Rc<dyn std::any::Any>)
+                          -> std::rc::Rc<dyn std::any::Any> {
+        match self.arg_transforms.get(&(requested_trait, method)) {
+            Some(transform) => transform(args),
+            None => args,
         }
     }
 
-    /// Notifies the behaviour that it has been matched.
-    pub fn matched(&self) {
-        self.num_matches.set(self.num_matches.get() + 1);
+    /// Sets the real implementation the mock delegates its calls to, turning it into a *spy*.
+    ///
+    /// The dispatch code records a call by the usual matching of behaviours and then calls through to the target,
+    /// which allows verifying the interactions without stubbing the return values.
+    /// Trait objects can be stored by wrapping them, e.g., as `Box<Trait>`.
+    pub fn set_spy_target<T: 'static>(&mut self, target: std::rc::Rc<T>) {
+        self.spy_delegate = Some(target);
     }
 
-    /// Returns `true` iff current number of matches would satify the behaviours expected repetitions.
-    pub fn is_saturated(&self) -> bool {
-        self.expected_min_matches.unwrap_or(0) <= self.num_matches.get() &&
-        self.num_matches.get() <= self.expected_max_matches.unwrap_or(std::usize::MAX)
+    /// Returns the spy's real implementation if it is of type `T`.
+    pub fn spy_target<T: 'static>(&self) -> Option<&T> {
+        self.spy_delegate.as_ref().and_then(|target| target.downcast_ref::<T>())
     }
 
-    /// Returns a description of the behaviour.
-    pub fn describe(&self) -> &str {
-        &self.stmt_repr
+    /// Attaches a value of type `T` to the mock, replacing a previously attached value of the same type.
+    ///
+    /// The values are not used by the mock itself, they allow test helpers to keep auxiliary state alongside the mock.
+    pub fn set_user_data<T: 'static>(&self, value: T) {
+        self.user_data.borrow_mut().insert(std::any::TypeId::of::<T>(), Box::new(value));
+    }
+
+    /// Returns the attached value of type `T`, if any.
+    ///
+    /// The returned reference borrows the mock's user data, attaching values while it is alive panics.
+    pub fn user_data<T: 'static>(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.user_data.borrow(), |user_data| {
+            user_data.get(&std::any::TypeId::of::<T>()).and_then(|value| value.downcast_ref::<T>())
+        }).ok()
+    }
+
+    /// Passing `true` makes `add_expect_behaviour()` behave like `add_expect_behaviour_deduplicated()`.
+    ///
+    /// The policy is disabled by default.
+    pub fn should_deduplicate_expectations(&mut self, flag: bool) {
+        self.deduplicate_expectations = flag;
+    }
+
+    /// Passing `true` requires the *expected* behaviours of the trait's method to be consumed in registration order.
+    ///
+    /// A behaviour matched before all behaviours registered earlier for the method have been satisfied
+    /// fails the verification, naming both the behaviour which should have been matched and the one which was.
+    /// Unlike ordering constraints between labelled behaviours, this applies to the whole queue of one method.
+    /// The policy is disabled by default.
+    pub fn should_consume_expectations_in_order(&mut self,
+                                                requested_trait: &'static str,
+                                                method: &'static str,
+                                                flag: bool) {
+        if flag {
+            self.in_order_methods.insert((requested_trait, method));
+        } else {
+            self.in_order_methods.remove(&(requested_trait, method));
+        }
+    }
+
+    /// Enables an *expected* behaviour unless a behaviour with the same `stmt_id` is registered for the trait's method.
+    ///
+    /// Behaviours are only duplicates if their bound variables are equal as well, see `ExpectBehaviour::with_bound_eq()`.
+    /// Without a comparator on the registered behaviour only identical bound variables are considered equal.
+    ///
+    /// Instead of adding a duplicate, e.g., if the same `expect_interactions!`-block is executed repeatedly,
+    /// the expected bounds of the duplicate are added to the bounds of the registered behaviour.
+    /// Thus executing a block defining `times(1)` twice expects two matches.
+    /// Missing bounds are treated as `0` for the minimum and as unbounded for the maximum.
+    /// A registered behaviour with a custom count predicate is kept unchanged.
+    pub fn add_expect_behaviour_deduplicated(&self,
+                                             requested_trait: &'static str,
+                                             method: &'static str,
+                                             behaviour: ExpectBehaviour) {
+        self.assert_accepts_behaviours(requested_trait, method);
+        if let Some(behaviour) = self.merge_into_duplicate(requested_trait, method, behaviour) {
+            self.push_expect_behaviour(requested_trait, method, behaviour);
+        }
+    }
+
+    /// Merges the `behaviour` into a registered behaviour with the same `stmt_id`.
+    ///
+    /// Returns the behaviour if no such behaviour is registered.
+    fn merge_into_duplicate(&self,
+                            requested_trait: &'static str,
+                            method: &'static str,
+                            behaviour: ExpectBehaviour)
+                            -> Option<ExpectBehaviour> {
+        let mut expect_behaviours = self.expect_behaviours.borrow_mut();
+        let duplicate = expect_behaviours.get_mut(&(requested_trait, method))
+                                         .and_then(|behaviours| {
+                                             behaviours.iter_mut()
+                                                       .find(|b| b.stmt_id == behaviour.stmt_id && b.has_equal_bound(&behaviour))
+                                         });
+        match duplicate {
+            Some(duplicate) => {
+                duplicate.merge_bounds(&behaviour);
+                None
+            }
+            None => Some(behaviour),
+        }
+    }
+
+    /// Registers the `behaviour` with the mock without deduplication.
+    fn push_expect_behaviour(&self, requested_trait: &'static str, method: &'static str, mut behaviour: ExpectBehaviour) {
+        self.assert_accepts_behaviours(requested_trait, method);
+        if cfg!(feature = "disabled") {
+            return;
+        }
+        debug_assert!(self.is_known_method(requested_trait, method),
+                      "Behaviour added for the unknown method {}::{}: {}",
+                      requested_trait,
+                      method,
+                      behaviour.describe());
+        behaviour.registration = Some(self.registration_for(requested_trait, method));
+        self.remove_consumed_expectations();
+        self.expect_behaviours
+            .borrow_mut()
+            .entry((requested_trait, method))
+            .or_default()
+            .push(behaviour);
+    }
+
+    /// Passing `true` enables recording the calls received by the mock in its call log.
+    ///
+    /// The policy is disabled by default.
+    /// See `log_call()`.
+    pub fn should_log_calls(&mut self, flag: bool) {
+        self.log_calls = flag;
+    }
+
+    /// Returns `true` iff calls received by the mock are recorded.
+    ///
+    /// The generated dispatch code should check this before rendering the arguments of a call
+    /// so no formatting is performed if the call log is disabled.
+    pub fn is_logging_calls(&self) -> bool {
+        self.log_calls
+    }
+
+    /// For *internal* use only.
+    ///
+    /// Records a call of a trait's method if call logging is enabled.
+    ///
+    /// # Arguments
+    /// * `requested_trait` - the trait's name
+    /// * `method` - the trait's method's name
+    /// * `args_repr` - the `Debug` representation of the call's arguments
+    pub fn log_call(&self, requested_trait: &'static str, method: &'static str, args_repr: String) {
+        if self.log_calls && !cfg!(feature = "disabled") {
+            self.shared().push_bounded(&mut self.call_log.borrow_mut(),
+                                       CallLogEntry {
+                                           requested_trait,
+                                           method,
+                                           args_repr,
+                                       });
+        }
+    }
+
+    /// Replays the calls recorded in the call log of this mock against the `target` mock.
+    ///
+    /// Each logged call is counted by the target, see `record_call()`, and appended to its call log if it logs calls.
+    /// Afterwards `dispatch` is invoked with the target and the entry to drive the target's behaviours,
+    /// e.g., by selecting and matching them for the arguments the entry's `args_repr` stands for.
+    /// As the log only keeps the `Debug` representation of the arguments, the mapping back to the arguments
+    /// is up to `dispatch`; a no-op dispatch only replays the counts and the log.
+    pub fn replay_into<F>(&self, target: &MockState, dispatch: F)
+        where F: Fn(&MockState, &CallLogEntry)
+    {
+        for entry in self.call_log.borrow().iter() {
+            target.record_call(entry.requested_trait, entry.method);
+            target.log_call(entry.requested_trait, entry.method, entry.args_repr.clone());
+            dispatch(target, entry);
+        }
+    }
+
+    /// Counts a call of a trait's method and returns its ordinal, starting at `1`.
+    ///
+    /// The dispatch code should record each call before matching its behaviours,
+    /// the count determines which call behaviours created by `ExpectBehaviour::with_nth_match()` apply to.
+    pub fn record_call(&self, requested_trait: &'static str, method: &'static str) -> usize {
+        if cfg!(feature = "disabled") {
+            return 0;
+        }
+        let count = {
+            let mut call_counts = self.shared().call_counts.borrow_mut();
+            let count = call_counts.entry((requested_trait, method)).or_insert(0);
+            *count += 1;
+            *count
+        };
+        if let Some(spanning) = self.expect_behaviours.borrow().get(&SPANNING_KEY) {
+            spanning.iter()
+                    .filter(|b| b.spanned_methods.contains(&(requested_trait, method)))
+                    .for_each(ExpectBehaviour::matched);
+        }
+        count
+    }
+
+    /// Enables an *expected* behaviour created by `ExpectBehaviour::spanning()`.
+    ///
+    /// The behaviour is matched by `record_call()` for each call of one of its spanned methods.
+    pub fn add_spanning_expect_behaviour(&self, mut behaviour: ExpectBehaviour) {
+        for &(requested_trait, method) in behaviour.spanned_methods.iter() {
+            self.assert_accepts_behaviours(requested_trait, method);
+            debug_assert!(self.is_known_method(requested_trait, method),
+                          "Behaviour added for the unknown method {}::{}: {}",
+                          requested_trait,
+                          method,
+                          behaviour.describe());
+        }
+        if cfg!(feature = "disabled") {
+            return;
+        }
+        behaviour.registration = Some(self.registration_for(SPANNING_KEY.0, SPANNING_KEY.1));
+        self.expect_behaviours
+            .borrow_mut()
+            .entry(SPANNING_KEY)
+            .or_default()
+            .push(behaviour);
+    }
+
+    /// Returns how often the trait's method has been invoked, independent of how many behaviours matched each call.
+    ///
+    /// Unlike the match counts of the behaviours this counts every call recorded by `record_call()` or
+    /// `MockControl::count_call()` exactly once, no behaviour needs to be registered for the method.
+    pub fn invocation_count(&self, requested_trait: &'static str, method: &'static str) -> usize {
+        self.shared.get()
+            .and_then(|shared| shared.call_counts.borrow().get(&(requested_trait, method)).cloned())
+            .unwrap_or(0)
+    }
+
+    /// Returns the trait, method, and `stmt_id` of the *given* behaviour which handled the `call_index`-th call, starting at `0`.
+    ///
+    /// The calls are counted across all methods of the mock by the matches of *given* behaviours in the invocation timeline,
+    /// calls which matched only *expected* behaviours are not counted. Returns `None` if there are fewer calls.
+    pub fn behaviour_for_call(&self, call_index: usize) -> Option<(&'static str, &'static str, usize)> {
+        self.shared.get().and_then(|shared| {
+            shared.timeline.borrow()
+                  .iter()
+                  .filter(|record| record.kind == BehaviourKind::Given)
+                  .nth(call_index)
+                  .map(|record| (record.requested_trait, record.method, record.stmt_id))
+        })
+    }
+
+    /// Returns how many of the `window` most recent entries of the invocation timeline are matches of the trait's method.
+    ///
+    /// The timeline records matches of behaviours, a call matching several behaviours therefore occupies several entries.
+    pub fn calls_in_last(&self, requested_trait: &'static str, method: &'static str, window: usize) -> usize {
+        self.shared.get().map_or(0, |shared| {
+            shared.timeline.borrow()
+                  .iter()
+                  .rev()
+                  .take(window)
+                  .filter(|record| record.requested_trait == requested_trait && record.method == method)
+                  .count()
+        })
+    }
+
+    /// Records a call of a trait's method which matched no behaviour.
+    ///
+    /// The dispatch code should pass the behaviours it tried, i.e., their descriptions and,
+    /// if available, why their matcher rejected the call's arguments (see `ArgMatcher::explain()`).
+    pub fn record_unexpected_call(&self,
+                                  requested_trait: &'static str,
+                                  method: &'static str,
+                                  candidates: Vec<TriedCandidate>) {
+        if cfg!(feature = "disabled") {
+            return;
+        }
+        self.unexpected_calls.borrow_mut().push(UnexpectedCall {
+            requested_trait,
+            method,
+            candidates,
+        });
+    }
+
+    /// Returns the calls which matched no behaviour in the order of their occurrence.
+    pub fn unexpected_call_details(&self) -> Vec<UnexpectedCall> {
+        self.unexpected_calls.borrow().clone()
+    }
+
+    /// Limits the number of entries kept in the invocation timeline and in the call log to `limit` each.
+    ///
+    /// Once the limit is reached the oldest entries are dropped, see `are_records_truncated()`.
+    /// The match counts of the behaviours and the invocation counts stay exact.
+    /// Both records are unbounded by default.
+    pub fn set_max_recorded_calls(&self, limit: usize) {
+        let shared = self.shared();
+        shared.max_recorded_calls.set(Some(limit));
+        let excess = shared.timeline.borrow().len().saturating_sub(limit);
+        let log_excess = self.call_log.borrow().len().saturating_sub(limit);
+        if excess > 0 || log_excess > 0 {
+            shared.timeline.borrow_mut().drain(..excess);
+            self.call_log.borrow_mut().drain(..log_excess);
+            shared.records_truncated.set(true);
+        }
+    }
+
+    /// Returns `true` iff entries have been dropped from the timeline or the call log, see `set_max_recorded_calls()`.
+    pub fn are_records_truncated(&self) -> bool {
+        self.shared.get().is_some_and(|shared| shared.records_truncated.get())
+    }
+
+    /// Returns the calls which matched no behaviour in the order of their occurrence and forgets them.
+    ///
+    /// Unlike `unexpected_call_details()` later inspections only see the calls recorded afterwards.
+    pub fn drain_unexpected_calls(&self) -> Vec<UnexpectedCall> {
+        std::mem::take(&mut *self.unexpected_calls.borrow_mut())
+    }
+
+    /// Verifies that the trait's method has been called with exactly the `expected` arguments in the given order.
+    ///
+    /// The arguments are compared by their `Debug` representation as recorded in the call log,
+    /// the error reports the first call at which the recorded arguments diverge from the expected ones.
+    /// Fails if call logging is disabled, see `should_log_calls()`.
+    pub fn verify_call_log(&self,
+                           requested_trait: &'static str,
+                           method: &'static str,
+                           expected: &[String])
+                           -> Result<(), VerificationError> {
+        if cfg!(feature = "disabled") {
+            return Ok(());
+        }
+        if !self.log_calls {
+            return Err(VerificationError::new(vec![
+                format!("Call log of {}::{} can't be verified as call logging is disabled", requested_trait, method)
+            ]));
+        }
+        let call_log = self.call_log.borrow();
+        let actual: Vec<&str> = call_log.iter()
+                                        .filter(|entry| entry.requested_trait == requested_trait && entry.method == method)
+                                        .map(|entry| entry.args_repr.as_str())
+                                        .collect();
+        for index in 0..std::cmp::max(actual.len(), expected.len()) {
+            let failure = match (expected.get(index), actual.get(index)) {
+                (Some(expected), Some(&actual)) if expected == actual => continue,
+                (Some(expected), Some(actual)) => format!("expected `{}`, got `{}`", expected, actual),
+                (Some(expected), None) => format!("expected `{}`, but the method has not been called", expected),
+                (None, Some(actual)) => format!("expected no further call, got `{}`", actual),
+                (None, None) => unreachable!(),
+            };
+            return Err(VerificationError::new(vec![
+                format!("Call #{} of {}::{} diverges from the expected call log: {}", index, requested_trait, method, failure)
+            ]));
+        }
+        Ok(())
+    }
+
+    /// Creates a matcher which accepts the calls of the trait's method whose index satisfies the `predicate`.
+    ///
+    /// The index of the first call is `0`, e.g., `|index| index > 0` rejects only the first call.
+    /// The index is the number of calls counted by `record_call()` minus one. The dispatch code records the call
+    /// before matching its arguments, so the matcher sees the index of the call being matched.
+    /// The matcher rejects all calls if none has been recorded.
+    pub fn on_call<P: Fn(usize) -> bool>(&self,
+                                         requested_trait: &'static str,
+                                         method: &'static str,
+                                         predicate: P)
+                                         -> matchers::OnCall<P> {
+        matchers::OnCall::new(self.shared().clone(), requested_trait, method, predicate)
+    }
+
+    /// Returns the calls recorded by the mock in the order of their occurrence.
+    pub fn call_log(&self) -> Vec<CallLogEntry> {
+        self.call_log.borrow().iter().cloned().collect()
+    }
+
+    /// Verifies the *expected* behaviours of `group` as a whole instead of individually.
+    ///
+    /// A behaviour which is a member of a group is no longer required to be satisfied on its own,
+    /// i.e., group membership relaxes the individual requirement.
+    /// Groups are removed by `reset_expected_behaviours()`.
+    pub fn add_expect_group(&self, group: ExpectGroup) {
+        self.expect_groups.borrow_mut().push(group);
+    }
+
+    /// Verifies that exactly `exactly` of the *expected* behaviours addressed by `members` are satisfied.
+    ///
+    /// The members are addressed like the ones of an `ExpectGroup`, members which are not registered count as unsatisfied.
+    /// The error lists the satisfied and the unsatisfied members.
+    pub fn verify_quorum(&self,
+                         members: &[(&'static str, &'static str, usize)],
+                         exactly: usize)
+                         -> Result<(), VerificationError> {
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let (satisfied, unsatisfied): (Vec<_>, Vec<_>) = members.iter().partition(|&&(requested_trait, method, stmt_id)| {
+            expect_behaviours.get(&(requested_trait, method))
+                             .and_then(|behaviours| behaviours.iter().find(|b| b.stmt_id == stmt_id))
+                             .is_some_and(|b| b.is_saturated())
+        });
+        if satisfied.len() == exactly {
+            return Ok(());
+        }
+
+        let render = |members: Vec<&(&'static str, &'static str, usize)>| {
+            members.iter().map(|m| format!("{}::{} #{}", m.0, m.1, m.2)).collect::<Vec<_>>().join(", ")
+        };
+        Err(VerificationError::new(vec![format!("Expected exactly {} of {} behaviours to be satisfied but {} are: \
+                                                 satisfied [{}], unsatisfied [{}]",
+                                                exactly,
+                                                members.len(),
+                                                satisfied.len(),
+                                                render(satisfied),
+                                                render(unsatisfied))]))
+    }
+
+    /// Deactivates all behaviours activated by a `expect_interactions!`-block before except for *cumulative* ones.
+    ///
+    /// The cumulative behaviours retain their match counts so they are verified against the matches accumulated across resets.
+    /// See `ExpectBehaviour::with_cumulative()`.
+    pub fn reset_expected_behaviours_preserving_cumulative(&mut self) {
+        self.reset_expected_behaviours_except(|behaviour| behaviour.is_cumulative());
+    }
+
+    /// Deactivates all behaviours activated by a `expect_interactions!`-block before except for those satisfying `predicate`.
+    ///
+    /// The retained behaviours keep their match counts, the others are dropped completely.
+    /// E.g., `reset_expected_behaviours_except(|b| b.label() == Some("persistent"))` keeps all expectations labelled as persistent.
+    /// Unlike `reset_expected_behaviours()` the expectation groups are kept.
+    pub fn reset_expected_behaviours_except<P>(&mut self, predicate: P)
+        where P: Fn(&ExpectBehaviour) -> bool
+    {
+        let mut expect_behaviours = self.expect_behaviours.borrow_mut();
+        for behaviours in expect_behaviours.values_mut() {
+            behaviours.retain(|behaviour| predicate(behaviour));
+        }
+        expect_behaviours.retain(|_, behaviours| !behaviours.is_empty());
+    }
+
+    /// Freezes the mock's behaviours, adding further *given* or *expected* behaviours panics afterwards.
+    ///
+    /// This catches behaviours accidentally defined after the arrange phase of a test.
+    /// Resetting the behaviours is still allowed and does not unfreeze the mock, see `unfreeze()`.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Allows adding behaviours again after `freeze()`.
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    /// Returns `true` iff the mock's behaviours are frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Panics if the mock is frozen or is being verified.
+    ///
+    /// Registering a behaviour while the verification iterates over the behaviours, e.g., from a callback,
+    /// would otherwise fail with an opaque `BorrowMutError`.
+    fn assert_accepts_behaviours(&self, requested_trait: &'static str, method: &'static str) {
+        if self.frozen {
+            panic!("Behaviour added for {}::{} after the mock has been frozen", requested_trait, method);
+        }
+        if self.verifying.get() {
+            panic!("Behaviour added for {}::{}: cannot register behaviours during verification", requested_trait, method);
+        }
+    }
+
+    /// Marks the mock as being verified until the returned guard is dropped.
+    fn enter_verification(&self) -> VerifyingGuard<'_> {
+        VerifyingGuard { verifying: &self.verifying, previous: self.verifying.replace(true) }
+    }
+
+    /// Registers the trait/method keys behaviours may be added for.
+    ///
+    /// Once keys are registered, adding a behaviour for an unknown key panics in debug builds.
+    /// This catches typos in the trait and method names supplied by the code generating the mock.
+    /// Wildcard behaviours are accepted for any trait with a registered method.
+    pub fn register_known_methods(&mut self, methods: &[(&'static str, &'static str)]) {
+        self.known_methods.get_or_insert_with(std::collections::HashSet::new).extend(methods.iter().cloned());
+    }
+
+    /// Returns `true` iff behaviours may be added for the trait's method.
+    fn is_known_method(&self, requested_trait: &'static str, method: &'static str) -> bool {
+        match self.known_methods {
+            None => true,
+            Some(ref known) if method == ANY_METHOD => known.iter().any(|&(known_trait, _)| known_trait == requested_trait),
+            Some(ref known) => known.contains(&(requested_trait, method)),
+        }
+    }
+
+    /// Enables several *given* behaviours for a trait's method at once.
+    ///
+    /// The behaviours are added in the iteration order while borrowing the mock's given behaviours only once,
+    /// the iterator must not access the mock's behaviours.
+    pub fn add_given_behaviours<I>(&self, requested_trait: &'static str, method: &'static str, behaviours: I)
+        where I: IntoIterator<Item = GivenBehaviour>
+    {
+        self.assert_accepts_behaviours(requested_trait, method);
+        if cfg!(feature = "disabled") {
+            return;
+        }
+        debug_assert!(self.is_known_method(requested_trait, method),
+                      "Behaviours added for the unknown method {}::{}",
+                      requested_trait,
+                      method);
+        let mut given_behaviours = self.given_behaviours.borrow_mut();
+        let registered = given_behaviours.entry((requested_trait, method)).or_default();
+        for mut behaviour in behaviours {
+            behaviour.registration = Some(self.registration_for(requested_trait, method));
+            registered.push(behaviour);
+        }
+    }
+
+    /// Enables several *expected* behaviours for a trait's method at once.
+    ///
+    /// See `add_given_behaviours()`. If expectations are deduplicated each behaviour is added separately.
+    pub fn add_expect_behaviours<I>(&self, requested_trait: &'static str, method: &'static str, behaviours: I)
+        where I: IntoIterator<Item = ExpectBehaviour>
+    {
+        self.assert_accepts_behaviours(requested_trait, method);
+        if cfg!(feature = "disabled") {
+            return;
+        }
+        if self.deduplicate_expectations {
+            for behaviour in behaviours {
+                self.add_expect_behaviour_deduplicated(requested_trait, method, behaviour);
+            }
+            return;
+        }
+        debug_assert!(self.is_known_method(requested_trait, method),
+                      "Behaviours added for the unknown method {}::{}",
+                      requested_trait,
+                      method);
+        self.remove_consumed_expectations();
+        let mut expect_behaviours = self.expect_behaviours.borrow_mut();
+        let registered = expect_behaviours.entry((requested_trait, method)).or_default();
+        for mut behaviour in behaviours {
+            behaviour.registration = Some(self.registration_for(requested_trait, method));
+            registered.push(behaviour);
+        }
+    }
+
+    /// Enables a *given* behaviour for all methods of `requested_trait`.
+    ///
+    /// The behaviour is registered under the `ANY_METHOD` wildcard.
+    /// See `given_behaviours_for()` for how wildcard behaviours are looked up.
+    pub fn add_given_behaviour_wildcard(&self, requested_trait: &'static str, behaviour: GivenBehaviour) {
+        self.add_given_behaviour(requested_trait, ANY_METHOD, behaviour);
+    }
+
+    /// Returns the *given* behaviours which apply to a trait's method.
+    ///
+    /// Behaviours registered for the exact method take precedence,
+    /// the wildcard behaviours of the trait are only returned if there are no behaviours for the exact method.
+    /// Returns `None` if neither exist.
+    pub fn given_behaviours_for(&self,
+                                requested_trait: &'static str,
+                                method: &'static str)
+                                -> Option<Ref<'_, Vec<GivenBehaviour>>> {
+        Ref::filter_map(self.given_behaviours.borrow(), |behaviours| {
+            behaviours.get(&(requested_trait, method))
+                      .filter(|vs| !vs.is_empty())
+                      .or_else(|| behaviours.get(&(requested_trait, ANY_METHOD)))
+        }).ok()
+    }
+
+    /// Verifies the behaviours of the mock without printing or panicking.
+    ///
+    /// See `MockControl::are_expected_behaviours_satisfied()` for the conditions checked.
+    pub fn verification_report(&self) -> VerificationReport {
+        let mut report = VerificationReport::new();
+        if cfg!(feature = "disabled") {
+            return report;
+        }
+        self.remove_consumed_expectations();
+        let _verifying = self.enter_verification();
+        let expect_groups = self.expect_groups.borrow();
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let is_grouped = |key: &(&'static str, &'static str), behaviour: &ExpectBehaviour| {
+            expect_groups.iter().any(|g| g.contains(key.0, key.1, behaviour.stmt_id))
+        };
+        let mut keys: Vec<&(&'static str, &'static str)> = expect_behaviours.keys().collect();
+        keys.sort();
+        let mut ungrouped_behaviours: Vec<&ExpectBehaviour> = keys.into_iter().flat_map(|key| {
+            expect_behaviours[key].iter().filter(move |b| b.is_enabled() && !is_grouped(key, b))
+        }).collect();
+        match self.report_ordering {
+            ReportOrdering::RegistrationOrder => {}
+            ReportOrdering::BySeverity => ungrouped_behaviours.sort_by_key(|b| !b.is_over_saturated()),
+            ReportOrdering::ByDistanceFromTarget => {
+                ungrouped_behaviours.sort_by_key(|b| std::cmp::Reverse(b.distance_from_target()))
+            }
+        }
+        ungrouped_behaviours.sort_by_key(|b| std::cmp::Reverse(b.verify_priority));
+        for behaviour in ungrouped_behaviours {
+            if self.treat_at_most_as_nonfatal && behaviour.is_over_saturated() {
+                let message = format!("Behaviour exceeded its maximum with {} matching invocations: {}",
+                                      behaviour.num_matches.get(),
+                                      behaviour.describe_with_bound());
+                self.add_behaviour_warning(&mut report, behaviour, message);
+            } else if behaviour.soft && !behaviour.is_saturated() {
+                let message = format!("Soft behaviour unsatisfied with {} matching invocations: {}",
+                                      behaviour.num_matches.get(),
+                                      behaviour.describe_with_bound());
+                self.add_behaviour_warning(&mut report, behaviour, message);
+            } else if !behaviour.is_saturated() {
+                let message = format!("Behaviour unsatisfied with {} matching invocations: {}",
+                                      behaviour.num_matches.get(),
+                                      behaviour.describe_with_bound());
+                self.add_behaviour_failure(&mut report, behaviour, message);
+                if self.report_filter.as_ref().is_none_or(|filter| filter(behaviour)) {
+                    if let Some(ref registration) = behaviour.registration {
+                        report.add_unsatisfied(behaviour.summary(registration.requested_trait, registration.method));
+                    }
+                }
+                for violation in behaviour.args_violations.borrow().iter() {
+                    let message = format!("Behaviour violated argument constraint on match #{}: {}: {}",
+                                          violation.call_index,
+                                          violation.message,
+                                          behaviour.describe_with_bound());
+                    self.add_behaviour_failure(&mut report, behaviour, message);
+                }
+                if let Some(requirements) = behaviour.unmet_args_requirements() {
+                    let message = format!("Behaviour unsatisfied as {}: {}", requirements, behaviour.describe_with_bound());
+                    self.add_behaviour_failure(&mut report, behaviour, message);
+                }
+            }
+        }
+        for group in expect_groups.iter() {
+            let members: Vec<&ExpectBehaviour> = expect_behaviours.iter()
+                .flat_map(|(key, behaviours)| {
+                    behaviours.iter().filter(move |b| b.is_enabled() && group.contains(key.0, key.1, b.stmt_id))
+                })
+                .collect();
+            let num_saturated = members.iter().filter(|b| b.is_saturated()).count();
+            if num_saturated < group.quorum {
+                let descriptions: Vec<String> = members.iter().map(|b| b.describe_with_bound()).collect();
+                let message = format!("Behaviour group unsatisfied with {} of {} required behaviours satisfied: {}",
+                                      num_saturated,
+                                      group.quorum,
+                                      descriptions.join(" | "));
+                if !members.is_empty() && members.iter().all(|b| b.soft) {
+                    report.add_warning(message);
+                } else {
+                    report.add_failure(message);
+                }
+            }
+        }
+        let all_behaviours = || expect_behaviours.values().flatten().filter(|b| b.is_enabled());
+        for behaviour in all_behaviours() {
+            if behaviour.premature_matches() > 0 {
+                let message = format!("Behaviour matched {} times before its phase {} was active: {}",
+                                      behaviour.premature_matches(),
+                                      behaviour.phase.unwrap_or(0),
+                                      behaviour.describe_with_bound());
+                self.add_behaviour_failure(&mut report, behaviour, message);
+            }
+        }
+        for behaviour in all_behaviours() {
+            for constraint in behaviour.ordering.iter() {
+                let aliased = self.resolve_alias(constraint.label());
+                let labelled = expect_behaviours.iter().flat_map(|(&(requested_trait, method), behaviours)| {
+                    behaviours.iter().filter(move |b| {
+                        b.is_enabled() &&
+                        (b.label() == Some(constraint.label()) ||
+                         aliased == Some((requested_trait, method, b.stmt_id)))
+                    })
+                });
+                for other in labelled {
+                    if behaviour.violates_ordering(constraint, other) {
+                        let relation = match *constraint {
+                            OrderingConstraint::Before(_) => "before",
+                            OrderingConstraint::After(_) => "after",
+                        };
+                        let message = format!("Behaviour not matched {} `{}`: {}",
+                                              relation,
+                                              constraint.label(),
+                                              behaviour.describe_with_bound());
+                        self.add_behaviour_failure(&mut report, behaviour, message);
+                    }
+                }
+            }
+        }
+        let mut in_order_keys: Vec<&(&'static str, &'static str)> = self.in_order_methods.iter().collect();
+        in_order_keys.sort();
+        for key in in_order_keys {
+            let queue: Vec<&ExpectBehaviour> = expect_behaviours.get(key)
+                                                                .into_iter()
+                                                                .flatten()
+                                                                .filter(|b| b.is_enabled())
+                                                                .collect();
+            for (index, behaviour) in queue.iter().enumerate() {
+                let first_match = match behaviour.first_sequence_number.get() {
+                    Some(first_match) => first_match,
+                    None => continue,
+                };
+                let pending = queue[..index].iter().find(|earlier| {
+                    match earlier.saturated_sequence_number.get() {
+                        Some(saturated) => saturated > first_match,
+                        // Behaviours saturated before any match, e.g., `at_most`, never record when they became saturated.
+                        None => !earlier.is_saturated(),
+                    }
+                });
+                if let Some(pending) = pending {
+                    let message = format!("Behaviour matched out of order, expected `{}` to be satisfied first: {}",
+                                          pending.describe(),
+                                          behaviour.describe_with_bound());
+                    self.add_behaviour_failure(&mut report, behaviour, message);
+                }
+            }
+        }
+        if self.verify_no_unused_given {
+            let given_behaviours = self.given_behaviours.borrow();
+            let mut keys: Vec<&(&'static str, &'static str)> = given_behaviours.keys().collect();
+            keys.sort();
+            for behaviour in keys.into_iter().flat_map(|key| given_behaviours[key].iter()) {
+                if behaviour.is_enabled() && behaviour.num_matches.get() == 0 {
+                    report.add_failure(format!("Given behaviour never matched: {}", behaviour.describe_with_bound()));
+                }
+            }
+        }
+        if self.warn_on_fully_unused_methods {
+            let given_behaviours = self.given_behaviours.borrow();
+            let mut keys: Vec<&(&'static str, &'static str)> = given_behaviours.keys()
+                                                                               .chain(expect_behaviours.keys())
+                                                                               .collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys.into_iter().filter(|&&key| key != SPANNING_KEY) {
+                let given_matched = given_behaviours.get(key).is_some_and(|vs| vs.iter().any(|b| b.has_matched()));
+                let expect_matched = expect_behaviours.get(key).is_some_and(|vs| vs.iter().any(|b| b.has_matched()));
+                if !given_matched && !expect_matched {
+                    report.add_warning(format!("Method configured but never called: {}::{}", key.0, key.1));
+                }
+            }
+        }
+        report
+    }
+
+    /// Removes the *expected* behaviours which have been satisfied, see `ExpectBehaviour::with_remove_on_satisfy()`.
+    ///
+    /// A consumed behaviour is disabled when it becomes satisfied, as the dispatch code still borrows the behaviours
+    /// at that point; it is removed once the behaviours are no longer borrowed.
+    fn remove_consumed_expectations(&self) {
+        if let Ok(mut expect_behaviours) = self.expect_behaviours.try_borrow_mut() {
+            for behaviours in expect_behaviours.values_mut() {
+                behaviours.retain(|b| !b.consumed.get());
+            }
+        }
+    }
+
+    /// Returns the mock's verification report serialized as JSON, see `VerificationReport::unsatisfied_behaviours()`.
+    #[cfg(feature = "serde")]
+    pub fn verification_report_json(&self) -> String {
+        ::serde_json::to_string(&self.verification_report()).expect("a verification report is always serializable")
+    }
+
+    /// Returns a future which resolves once the mock's expected behaviours are satisfied or the `timeout` has elapsed.
+    ///
+    /// The future is woken by each match of the mock's behaviours instead of polling the verification.
+    /// On timeout it resolves to `VerificationError::Timeout` with the failures of the last verification.
+    /// See `eventually::VerifyEventually` for the runtime-agnostic wakeup mechanism.
+    #[cfg(feature = "async")]
+    pub fn verify_eventually(&self, timeout: std::time::Duration) -> eventually::VerifyEventually<'_> {
+        eventually::VerifyEventually::new(self, timeout)
+    }
+
+    /// Returns the current time of the mock's clock.
+    #[cfg(feature = "async")]
+    fn now(&self) -> std::time::Instant {
+        self.shared().now()
+    }
+
+    /// Registers a task which is woken by the next match of the mock's behaviours.
+    #[cfg(feature = "async")]
+    fn wake_on_next_match(&self, waker: &std::task::Waker) {
+        let mut wakers = self.shared().wakers.borrow_mut();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    /// Attaches the mock to a `SequenceClock` which hands out the sequence numbers of its matches from now on.
+    ///
+    /// The matches of all mocks attached to the same clock are numbered by a single counter without gaps,
+    /// so their timelines can be merged and ordered. A mock which is not attached numbers its matches by a private
+    /// counter starting at `0`, so the numbers of different unattached mocks, as well as the numbers of attached
+    /// and unattached mocks, must not be compared.
+    pub fn attach_sequence_clock(&self, sequence_clock: &SequenceClock) {
+        *self.shared().sequence_clock.borrow_mut() = Some(sequence_clock.clone());
+    }
+
+    /// Replaces the source of the mock's time readings, e.g., by a `ManualClock` to make timing features deterministic.
+    ///
+    /// The clock applies to the timestamps of matches, the time to live of *given* behaviours, and the deadline of
+    /// `verify_eventually()`. The default clock is the `SystemClock`.
+    pub fn set_clock(&self, clock: Box<dyn Clock>) {
+        *self.shared().clock.borrow_mut() = std::rc::Rc::from(clock);
+    }
+
+    /// Returns the phase whose *expected* behaviours are active, starting at `0`.
+    pub fn current_phase(&self) -> u32 {
+        self.shared.get().map_or(0, |shared| shared.current_phase.get())
+    }
+
+    /// Verifies the *expected* behaviours of the current phase and advances to the next phase if they are satisfied.
+    ///
+    /// Behaviours without a phase are not verified. Matches of behaviours of later phases are counted as premature
+    /// and fail the verification of the mock.
+    pub fn advance_phase(&self) -> Result<(), VerificationError> {
+        let current_phase = self.current_phase();
+        let failures: Vec<String> = self.expect_behaviours
+            .borrow()
+            .values()
+            .flatten()
+            .filter(|b| b.is_enabled() && b.phase == Some(current_phase) && !b.is_saturated())
+            .map(|b| {
+                format!("Behaviour of phase {} unsatisfied with {} matching invocations: {}",
+                        current_phase,
+                        b.num_matches.get(),
+                        b.describe())
+            })
+            .collect();
+        if !failures.is_empty() {
+            return Err(VerificationError::new(failures));
+        }
+        self.shared().current_phase.set(current_phase + 1);
+        Ok(())
+    }
+
+    /// Verifies the mock like `verify()` but returns the failures instead of panicking.
+    ///
+    /// Nothing is sent to the message sink. Unlike a successful `verify()` the verification on drop is not skipped.
+    pub fn try_verify(&self) -> Result<(), VerificationError> {
+        self.explicitly_verified.set(true);
+        let report = self.verification_report();
+        if report.is_satisfied() {
+            Ok(())
+        } else {
+            Err(VerificationError::new(report.failures().to_vec()))
+        }
+    }
+
+    /// Verifies that every enabled *expected* behaviour is matched within its bounds, neither below nor above.
+    ///
+    /// Behaviours below their minimum and above their maximum are reported with distinct messages,
+    /// the latter even if `should_treat_at_most_as_nonfatal()` is enabled.
+    /// The remaining failures of the regular verification, e.g., of argument constraints or groups, follow them.
+    pub fn assert_fully_satisfied(&self) -> Result<(), VerificationError> {
+        self.explicitly_verified.set(true);
+        let report = self.verification_report();
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let mut keys: Vec<&(&'static str, &'static str)> = expect_behaviours.keys().collect();
+        keys.sort();
+        let mut failures = Vec::new();
+        let mut generic_failures = Vec::new();
+        let behaviours = keys.into_iter().flat_map(|key| expect_behaviours[key].iter());
+        for behaviour in behaviours.filter(|b| b.is_enabled() && !b.soft) {
+            let mode = if behaviour.is_under_saturated() {
+                "below its minimum"
+            } else if behaviour.is_over_saturated() {
+                "above its maximum"
+            } else {
+                continue;
+            };
+            failures.push(format!("Behaviour {} with {} matching invocations, expected {}: {}",
+                                  mode,
+                                  behaviour.num_matches.get(),
+                                  behaviour.expected_repetitions(),
+                                  behaviour.describe_with_bound()));
+            generic_failures.push(format!("Behaviour unsatisfied with {} matching invocations: {}",
+                                          behaviour.num_matches.get(),
+                                          behaviour.describe_with_bound()));
+        }
+        failures.extend(report.failures().iter().filter(|f| !generic_failures.contains(f)).cloned());
+        if failures.is_empty() && report.is_satisfied() {
+            Ok(())
+        } else {
+            Err(VerificationError::new(failures))
+        }
+    }
+
+    /// Verifies the mock and, if it is satisfied, prepares it for the next iteration of a test.
+    ///
+    /// On success the *expected* behaviours are removed and the match counts of the *given* behaviours are reset.
+    /// On failure the mock is left unchanged for inspection.
+    pub fn verify_and_reset(&mut self) -> Result<(), VerificationError> {
+        self.try_verify()?;
+        self.reset_expected_behaviours();
+        self.reset_given_counts();
+        Ok(())
+    }
+
+    /// Resets the match counts of all *given* behaviours as if they had never been matched.
+    ///
+    /// Exhausted behaviours become available again.
+    pub fn reset_given_counts(&self) {
+        for behaviour in self.given_behaviours.borrow().values().flatten() {
+            behaviour.num_matches.set(0);
+            behaviour.last_sequence_number.set(None);
+        }
+    }
+
+    /// Returns the messages describing why the verification would fail, empty if it succeeds.
+    ///
+    /// The messages are the same as reported by `are_expected_behaviours_satisfied()` but nothing is sent to the message sink.
+    pub fn unsatisfied_descriptions(&self) -> Vec<String> {
+        self.verification_report().failures().to_vec()
+    }
+
+    /// Summarizes the state of the mock's expectations in a single value.
+    ///
+    /// Unexpected calls take precedence over the states of the *expected* behaviours.
+    /// Only the enabled behaviours are considered individually, groups and ordering constraints are not taken into account.
+    pub fn health(&self) -> MockHealth {
+        let num_unexpected_calls = self.unexpected_calls.borrow().len();
+        if num_unexpected_calls > 0 {
+            return MockHealth::HadUnexpectedCalls(num_unexpected_calls);
+        }
+
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let enabled = || expect_behaviours.values().flatten().filter(|b| b.is_enabled());
+        let over = enabled().filter(|b| b.is_over_saturated()).count();
+        let under = enabled().filter(|b| !b.is_saturated() && !b.is_over_saturated()).count();
+        match (under, over) {
+            (0, 0) => MockHealth::AllSatisfied,
+            (under, 0) => MockHealth::SomeUnderSaturated(under),
+            (0, over) => MockHealth::SomeOverSaturated(over),
+            (under, over) => MockHealth::Mixed { under, over },
+        }
+    }
+
+    /// Sends the warnings and failures of the `report` to the message sink.
+    fn send_to_sink(&self, report: &VerificationReport) {
+        for message in report.warnings() {
+            self.message_sink.warning(message);
+        }
+        let max_failures = self.max_reported_failures.unwrap_or(usize::MAX);
+        for message in report.failures().iter().take(max_failures) {
+            self.message_sink.failure(message);
+        }
+        if report.failures().len() > max_failures {
+            self.message_sink.failure(&format!("(and {} more)", report.failures().len() - max_failures));
+        }
+        if report.num_hidden_failures() > 0 {
+            self.message_sink.failure(&format!("{} failures hidden by the report filter", report.num_hidden_failures()));
+        }
+    }
+
+    /// Limits the number of failure messages sent to the message sink to `max_failures`.
+    ///
+    /// The remaining failures are summarized by a single `(and N more)` message. The limit only affects the output,
+    /// the verification still fails on any failure and `VerificationReport` and `VerificationError` list all of them.
+    /// The failures are unlimited by default.
+    pub fn set_max_reported_failures(&mut self, max_failures: usize) {
+        self.max_reported_failures = Some(max_failures);
+    }
+
+    /// Sets a filter selecting the *expected* behaviours whose failures and warnings are reported.
+    ///
+    /// The verification still considers all behaviours, the failures of filtered behaviours are only counted,
+    /// see `VerificationReport::num_hidden_failures()`.
+    pub fn set_report_filter(&mut self, filter: Box<dyn Fn(&ExpectBehaviour) -> bool>) {
+        self.report_filter = Some(filter);
+    }
+
+    /// Adds the failure of the *expected* `behaviour` to the `report`, hiding it if the behaviour doesn't pass the report filter.
+    ///
+    /// The failure of a soft behaviour is downgraded to a warning, see `ExpectBehaviour::with_soft()`.
+    fn add_behaviour_failure(&self, report: &mut VerificationReport, behaviour: &ExpectBehaviour, message: String) {
+        if behaviour.soft {
+            self.add_behaviour_warning(report, behaviour, message);
+        } else if self.report_filter.as_ref().is_none_or(|filter| filter(behaviour)) {
+            report.add_failure(message);
+        } else {
+            report.add_hidden_failure();
+        }
+    }
+
+    /// Adds the warning of the *expected* `behaviour` to the `report` unless the behaviour doesn't pass the report filter.
+    fn add_behaviour_warning(&self, report: &mut VerificationReport, behaviour: &ExpectBehaviour, message: String) {
+        if self.report_filter.as_ref().is_none_or(|filter| filter(behaviour)) {
+            report.add_warning(message);
+        }
+    }
+
+    /// Sets a callback which is invoked with the report of a failed verification right before `verify()` panics.
+    ///
+    /// The callback is also invoked if the verification on drop fails.
+    /// It is not invoked if the verification is skipped because the thread is already panicking.
+    pub fn set_on_verification_failure(&mut self, callback: Box<dyn FnMut(&VerificationReport)>) {
+        *self.on_verification_failure.borrow_mut() = Some(callback);
+    }
+
+    /// Panics with a **galvanic-assert** failure if some behaviour of the mock is not satisfied.
+    ///
+    /// The failure is reported in the same style as a failed `assert_that!` of **galvanic-assert**.
+    #[cfg(feature = "galvanic_assert_integration")]
+    pub fn assert_satisfied(&self) {
+        if let galvanic_assert::MatchResult::Failed { name, reason } = self.verification_report().into_match_result() {
+            panic!("\nFailed assertion of matcher: {}\n{}", name, reason);
+        }
+    }
+
+    /// Returns pairs of `(shadowing_id, shadowed_id)` of *given* behaviours which can never be selected.
+    ///
+    /// A behaviour is shadowed if it is registered after a behaviour for the same method which matches any arguments
+    /// (see `GivenBehaviour::with_matches_any_args()`), is never exhausted, and has no precondition.
+    /// Behaviours with a time to live don't shadow others as they expire, disabled behaviours are ignored.
+    /// The analysis assumes `SelectionStrategy::FirstMatch` and is based on registration order only.
+    /// The pairs are ordered by trait, method, and registration order.
+    pub fn find_shadowed_behaviours(&self) -> Vec<(usize, usize)> {
+        let given_behaviours = self.given_behaviours.borrow();
+        let mut keys: Vec<&(&'static str, &'static str)> = given_behaviours.keys().collect();
+        keys.sort();
+
+        let mut shadowed = Vec::new();
+        for key in keys {
+            let behaviours: Vec<&GivenBehaviour> = given_behaviours[key].iter().filter(|b| b.is_enabled()).collect();
+            let shadowing = behaviours.iter().position(|b| {
+                b.matches_any_args
+                && b.expected_matches.is_none()
+                && b.precondition.is_none()
+                && !b.has_ttl()
+            });
+            if let Some(position) = shadowing {
+                let shadowing_id = behaviours[position].stmt_id;
+                shadowed.extend(behaviours[position + 1..].iter().map(|b| (shadowing_id, b.stmt_id)));
+            }
+        }
+        shadowed
+    }
+
+    /// Returns pairs of `(never_id, required_id)` of *expected* behaviours which may contradict each other.
+    ///
+    /// A pair is reported if, for the same method, one behaviour must never be matched and the other must be matched at least once.
+    /// The library can't determine whether the behaviours' matchers overlap, the check is a heuristic based on the count bounds only.
+    /// The pairs are ordered by trait, method, and registration order.
+    pub fn find_conflicting_expectations(&self) -> Vec<(usize, usize)> {
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let mut keys: Vec<&(&'static str, &'static str)> = expect_behaviours.keys().collect();
+        keys.sort();
+
+        let mut conflicts = Vec::new();
+        for key in keys {
+            let behaviours: Vec<&ExpectBehaviour> = expect_behaviours[key].iter().filter(|b| b.is_enabled()).collect();
+            let never = behaviours.iter().filter(|b| b.expected_max_matches.get() == Some(0));
+            for never_behaviour in never {
+                let required = behaviours.iter().filter(|b| b.expected_min_matches.get().is_some_and(|min| min > 0));
+                conflicts.extend(required.map(|b| (never_behaviour.stmt_id, b.stmt_id)));
+            }
+        }
+        conflicts
+    }
+
+    /// Checks that each of the trait's `methods` has at least one registered *expected* behaviour.
+    ///
+    /// The mock doesn't know all methods of a trait, so the caller supplies them. The error lists the methods without expectations.
+    pub fn assert_all_methods_expected(&self,
+                                       requested_trait: &'static str,
+                                       methods: &[&'static str])
+                                       -> Result<(), String> {
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let missing: Vec<String> = methods.iter()
+                                          .filter(|&&method| {
+                                              expect_behaviours.get(&(requested_trait, method))
+                                                               .is_none_or(|behaviours| behaviours.is_empty())
+                                          })
+                                          .map(|method| format!("{}::{}", requested_trait, method))
+                                          .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Methods without expected behaviours: {}", missing.join(", ")))
+        }
+    }
+
+    /// Returns the number of *given* behaviours registered for the trait's method.
+    pub fn given_count(&self, requested_trait: &'static str, method: &'static str) -> usize {
+        self.given_behaviours.borrow().get(&(requested_trait, method)).map_or(0, |behaviours| behaviours.len())
+    }
+
+    /// Returns the number of *expected* behaviours registered for the trait's method.
+    pub fn expect_count(&self, requested_trait: &'static str, method: &'static str) -> usize {
+        self.expect_behaviours.borrow().get(&(requested_trait, method)).map_or(0, |behaviours| behaviours.len())
+    }
+
+    /// Returns the sum of the matches of all *given* and *expected* behaviours of the trait's method.
+    fn num_matches_of(&self, key: (&'static str, &'static str)) -> usize {
+        let given_matches: usize = self.given_behaviours
+                                       .borrow()
+                                       .get(&key)
+                                       .map_or(0, |behaviours| behaviours.iter().map(|b| b.num_matches.get()).sum());
+        let expect_matches: usize = self.expect_behaviours
+                                        .borrow()
+                                        .get(&key)
+                                        .map_or(0, |behaviours| behaviours.iter().map(|b| b.num_matches.get()).sum());
+        given_matches + expect_matches
+    }
+
+    /// Returns the sorted names of the trait's methods of which any *given* or *expected* behaviour has been matched.
+    ///
+    /// Behaviours registered for `ANY_METHOD` are not attributed to a method.
+    pub fn methods_called(&self, requested_trait: &'static str) -> Vec<&'static str> {
+        let given_behaviours = self.given_behaviours.borrow();
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let mut methods: Vec<&'static str> = given_behaviours.keys()
+                                                             .chain(expect_behaviours.keys())
+                                                             .filter(|key| key.0 == requested_trait && key.1 != ANY_METHOD)
+                                                             .map(|key| key.1)
+                                                             .collect();
+        methods.sort();
+        methods.dedup();
+        methods.retain(|&method| self.num_matches_of((requested_trait, method)) > 0);
+        methods
+    }
+
+    /// Marks the beginning of the act phase of a test at the current position of the invocation timeline.
+    ///
+    /// *Expected* behaviours created with `ExpectBehaviour::with_post_act_scope()` ignore all matches before the marker.
+    /// Calling it again moves the marker but doesn't change which matches have been counted already.
+    pub fn begin_act(&self) {
+        let shared = self.shared();
+        shared.act_position.set(Some(shared.timeline.borrow().len()));
+    }
+
+    /// Returns the position of the act marker in the invocation timeline, `None` if `begin_act()` has not been called.
+    pub fn act_position(&self) -> Option<usize> {
+        self.shared.get().and_then(|shared| shared.act_position.get())
+    }
+
+    /// Remembers the current number of matches of each trait's method, replacing the previous checkpoint.
+    ///
+    /// Unlike a reset the match counts are retained, see `matches_since_checkpoint()`.
+    pub fn checkpoint(&self) {
+        let keys: Vec<(&'static str, &'static str)> = self.given_behaviours
+                                                          .borrow()
+                                                          .keys()
+                                                          .chain(self.expect_behaviours.borrow().keys())
+                                                          .cloned()
+                                                          .collect();
+        let checkpoint = keys.into_iter().map(|key| (key, self.num_matches_of(key))).collect();
+        *self.checkpoint.borrow_mut() = checkpoint;
+    }
+
+    /// Returns how often the *given* and *expected* behaviours of the trait's method have been matched since the last checkpoint.
+    ///
+    /// Without a checkpoint all matches are counted.
+    pub fn matches_since_checkpoint(&self, requested_trait: &'static str, method: &'static str) -> usize {
+        let key = (requested_trait, method);
+        let at_checkpoint = self.checkpoint.borrow().get(&key).cloned().unwrap_or(0);
+        self.num_matches_of(key).saturating_sub(at_checkpoint)
+    }
+
+    /// Returns the number of distinct methods of the trait which have been called, see `methods_called()`.
+    pub fn distinct_methods_called(&self, requested_trait: &'static str) -> usize {
+        self.methods_called(requested_trait).len()
+    }
+
+    /// Returns `true` iff any *given* or *expected* behaviour of the trait's method has been matched.
+    ///
+    /// Unlike the verification this neither allocates nor formats any messages.
+    pub fn any_match(&self, requested_trait: &'static str, method: &'static str) -> bool {
+        let key = (requested_trait, method);
+        self.given_behaviours
+            .borrow()
+            .get(&key)
+            .is_some_and(|behaviours| behaviours.iter().any(|b| b.has_matched())) ||
+        self.expect_behaviours
+            .borrow()
+            .get(&key)
+            .is_some_and(|behaviours| behaviours.iter().any(|b| b.has_matched()))
+    }
+
+    /// Starts the fluent definition of an *expected* behaviour for a method of `requested_trait`.
+    ///
+    /// See `FluentExpectation` for the available count and ordering constraints.
+    pub fn expect(&self, requested_trait: &'static str) -> FluentExpectation<'_> {
+        FluentExpectation::new(self, requested_trait)
+    }
+
+    /// Returns summaries of the *expected* behaviours accepted by `filter`, ordered by trait, method, and `stmt_id`.
+    ///
+    /// Unlike references to the behaviours the summaries do not borrow the mock.
+    pub fn expect_behaviours_where<F>(&self, filter: F) -> Vec<BehaviourSummary>
+        where F: Fn(&ExpectBehaviour) -> bool
+    {
+        let mut summaries: Vec<BehaviourSummary> = self.expect_behaviours
+            .borrow()
+            .iter()
+            .flat_map(|(&(requested_trait, method), behaviours)| {
+                behaviours.iter().filter(|b| filter(b)).map(move |b| b.summary(requested_trait, method))
+            })
+            .collect();
+        summaries.sort_by_key(|summary| (summary.requested_trait, summary.method, summary.stmt_id));
+        summaries
+    }
+
+    /// Returns summaries of the satisfied *expected* behaviours, see `expect_behaviours_where()`.
+    pub fn saturated_expectations(&self) -> Vec<BehaviourSummary> {
+        self.expect_behaviours_where(|b| b.is_saturated())
+    }
+
+    /// Returns summaries of the unsatisfied *expected* behaviours, see `expect_behaviours_where()`.
+    pub fn unsatisfied_expectations(&self) -> Vec<BehaviourSummary> {
+        self.expect_behaviours_where(|b| !b.is_saturated())
+    }
+
+    /// Checks that all matches of the `inner` method occurred between the matches of the `open` and the `close` method.
+    ///
+    /// The methods are addressed by the names of their *trait* and *method*. The bracket spans from the first match
+    /// of `open` to the last match of `close`, e.g., all `write`s of a resource must happen after `open` and before `close`.
+    /// Returns an error listing the matches of `inner` outside of the bracket,
+    /// or naming the `open` or `close` method if it has never been called.
+    pub fn assert_bracketed(&self,
+                            inner: (&'static str, &'static str),
+                            open: (&'static str, &'static str),
+                            close: (&'static str, &'static str))
+                            -> Result<(), String> {
+        let timeline = self.invocation_timeline();
+        let sequence_numbers = |method: (&'static str, &'static str)| {
+            timeline.iter()
+                    .filter(move |record| (record.requested_trait, record.method) == method)
+                    .map(|record| record.sequence_number)
+        };
+        let opened = sequence_numbers(open).min()
+                                           .ok_or_else(|| format!("{}::{} has never been called", open.0, open.1))?;
+        let closed = sequence_numbers(close).max()
+                                            .ok_or_else(|| format!("{}::{} has never been called", close.0, close.1))?;
+        let outside: Vec<String> = sequence_numbers(inner)
+            .enumerate()
+            .filter(|&(_, sequence_number)| sequence_number <= opened || sequence_number >= closed)
+            .map(|(index, _)| format!("#{}", index))
+            .collect();
+        if outside.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Matches {} of {}::{} occurred outside of {}::{} and {}::{}",
+                        outside.join(", "),
+                        inner.0,
+                        inner.1,
+                        open.0,
+                        open.1,
+                        close.0,
+                        close.1))
+        }
+    }
+
+    /// Returns how often each *given* behaviour of the trait's method has been matched, keyed by its `stmt_id`.
+    ///
+    /// As the matched *given* behaviour determines the return value, this shows which statements produced
+    /// the method's results, e.g., whether a stub with `SelectionStrategy::WeightedRandom` exercised all branches.
+    /// Wildcard behaviours of the trait are not included.
+    pub fn return_statement_histogram(&self,
+                                      requested_trait: &'static str,
+                                      method: &'static str)
+                                      -> HashMap<usize, usize> {
+        self.given_behaviours
+            .borrow()
+            .get(&(requested_trait, method))
+            .into_iter()
+            .flatten()
+            .map(|b| (b.stmt_id, b.num_matches.get()))
+            .collect()
+    }
+
+    /// Returns the description, the expected count, and the actual count of each unsatisfied *expected* behaviour.
+    ///
+    /// The expected count is rendered by `ExpectBehaviour::expected_repetitions()`, e.g., `exactly 2`,
+    /// the actual count is the number of matches. The triples are sorted by trait, method, and `stmt_id`
+    /// and allow reporters to render expected and actual side by side without parsing the report's messages.
+    pub fn failure_diff(&self) -> Vec<(String, String, String)> {
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let mut keys: Vec<&(&'static str, &'static str)> = expect_behaviours.keys().collect();
+        keys.sort();
+        keys.into_iter()
+            .flat_map(|key| {
+                let mut behaviours: Vec<&ExpectBehaviour> = expect_behaviours[key].iter().collect();
+                behaviours.sort_by_key(|b| b.stmt_id);
+                behaviours
+            })
+            .filter(|b| b.is_enabled() && !b.is_saturated())
+            .map(|b| (b.describe_with_bound(), b.expected_repetitions(), b.num_matches.get().to_string()))
+            .collect()
+    }
+
+    /// Returns whether each *expected* behaviour is satisfied, keyed by its `stmt_id`.
+    ///
+    /// Ids are assumed to be unique across all methods of the mock. If several methods have a behaviour
+    /// with the same id, it is unspecified which one's state is returned, see `satisfaction_map_keyed()`.
+    pub fn satisfaction_map(&self) -> HashMap<usize, bool> {
+        self.satisfaction_map_keyed()
+            .into_iter()
+            .map(|((_, _, stmt_id), is_saturated)| (stmt_id, is_saturated))
+            .collect()
+    }
+
+    /// Returns whether each *expected* behaviour is satisfied, keyed by its trait, method, and `stmt_id`.
+    pub fn satisfaction_map_keyed(&self) -> HashMap<(&'static str, &'static str, usize), bool> {
+        self.expect_behaviours
+            .borrow()
+            .iter()
+            .flat_map(|(&(requested_trait, method), behaviours)| {
+                behaviours.iter().map(move |b| ((requested_trait, method, b.stmt_id), b.is_saturated()))
+            })
+            .collect()
+    }
+
+    /// Returns a deterministic dump of all behaviours, one behaviour per line.
+    ///
+    /// The lines are sorted by trait, method, kind (*given* before *expected*), and `stmt_id`, e.g.,
+    /// `GIVEN Animal::name #0 matches=3` and `EXPECT Animal::legs #2 matches=1 min=2 max=2 SATISFIED=false`.
+    /// Missing bounds are rendered as `-`. Unlike `interaction_summary()` the dump is meant for diffing CI artifacts.
+    pub fn dump_text(&self) -> String {
+        fn bound(bound: Option<usize>) -> String {
+            bound.map_or_else(|| "-".to_string(), |b| b.to_string())
+        }
+
+        let mut lines = Vec::new();
+        for (&(requested_trait, method), behaviours) in self.given_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                lines.push(((requested_trait, method, BehaviourKind::Given, behaviour.stmt_id),
+                            format!("GIVEN {}::{} #{} matches={}",
+                                    requested_trait,
+                                    method,
+                                    behaviour.stmt_id,
+                                    behaviour.num_matches.get())));
+            }
+        }
+        for (&(requested_trait, method), behaviours) in self.expect_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                lines.push(((requested_trait, method, BehaviourKind::Expect, behaviour.stmt_id),
+                            format!("EXPECT {}::{} #{} matches={} min={} max={} SATISFIED={}",
+                                    requested_trait,
+                                    method,
+                                    behaviour.stmt_id,
+                                    behaviour.num_matches.get(),
+                                    bound(behaviour.expected_min_matches.get()),
+                                    bound(behaviour.expected_max_matches.get()),
+                                    behaviour.is_saturated())));
+            }
+        }
+        lines.sort();
+        lines.into_iter().map(|(_, line)| line).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Returns a table comparing the expected with the actual matches of all *expected* behaviours.
+    ///
+    /// The columns are aligned for monospace output, the rows are sorted by trait, method, and `stmt_id`.
+    /// Unlike `dump_text()` the table is meant for humans reading the output of a failed test.
+    pub fn report_table(&self) -> String {
+        let mut rows = Vec::new();
+        for (&(requested_trait, method), behaviours) in self.expect_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                let status = if behaviour.is_saturated() { "satisfied" } else { "UNSATISFIED" };
+                rows.push(((requested_trait, method, behaviour.stmt_id),
+                           [requested_trait.to_string(),
+                            method.to_string(),
+                            behaviour.expected_repetitions(),
+                            behaviour.num_matches.get().to_string(),
+                            status.to_string()]));
+            }
+        }
+        rows.sort();
+
+        let header = ["trait", "method", "expected", "actual", "status"].map(|column| column.to_string());
+        let rows: Vec<[String; 5]> = std::iter::once(header).chain(rows.into_iter().map(|(_, row)| row)).collect();
+        let mut widths = [0; 5];
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = std::cmp::max(*width, cell.len());
+            }
+        }
+        rows.iter()
+            .map(|row| {
+                let cells: Vec<String> = row.iter()
+                                            .zip(widths.iter())
+                                            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+                                            .collect();
+                cells.join(" | ").trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Starts an assertion on the recorded number of calls of a trait's method.
+    ///
+    /// Unlike an *expected* behaviour the assertion needs no registration, it is evaluated against the matches recorded so far.
+    pub fn calls_of(&self, method: (&'static str, &'static str)) -> CallCountAssertion<'_> {
+        CallCountAssertion::new(self, method.0, method.1)
+    }
+
+    /// Returns a snapshot of the current match counts of all behaviours.
+    ///
+    /// Comparing snapshots with `MockStateSnapshot::diff()` shows the interactions between the two points in time.
+    pub fn snapshot(&self) -> MockStateSnapshot {
+        let mut snapshot = MockStateSnapshot::new();
+        for (&(requested_trait, method), behaviours) in self.given_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                snapshot.insert((requested_trait, method, BehaviourKind::Given, behaviour.stmt_id),
+                                behaviour.num_matches.get());
+            }
+        }
+        for (&(requested_trait, method), behaviours) in self.expect_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                snapshot.insert((requested_trait, method, BehaviourKind::Expect, behaviour.stmt_id),
+                                behaviour.num_matches.get());
+            }
+        }
+        snapshot
+    }
+
+    /// Saves the match counts of all behaviours and the invocation counts of all methods.
+    ///
+    /// Unlike `snapshot()` the saved counts can be written back by `restore_counts()`,
+    /// e.g., to explore several branches of a test from a common point.
+    pub fn save_counts(&self) -> CountSnapshot {
+        let mut counts = CountSnapshot::default();
+        for (&(requested_trait, method), behaviours) in self.given_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                counts.num_matches.insert((requested_trait, method, BehaviourKind::Given, behaviour.stmt_id),
+                                          behaviour.num_matches.get());
+            }
+        }
+        for (&(requested_trait, method), behaviours) in self.expect_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                counts.num_matches.insert((requested_trait, method, BehaviourKind::Expect, behaviour.stmt_id),
+                                          behaviour.num_matches.get());
+            }
+        }
+        counts.call_counts = self.shared.get().map_or_else(Default::default, |shared| {
+            shared.call_counts.borrow().iter().map(|(&key, &count)| (key, count)).collect()
+        });
+        counts
+    }
+
+    /// Writes the counts saved by `save_counts()` back to the behaviours and methods of the mock.
+    ///
+    /// Behaviours and methods which are not part of the snapshot, e.g., because they have been added later,
+    /// are reset to zero. Behaviours are identified by their trait, method, kind, and `stmt_id`.
+    /// Only the raw counts are restored: the invocation timeline and the call log are kept, and so is the state
+    /// behind constraints and orderings, i.e., argument constraints and their violations, premature matches,
+    /// consumed and disabled behaviours, caller contexts, and the sequence numbers of matches are not rewound.
+    pub fn restore_counts(&self, counts: &CountSnapshot) {
+        for (&(requested_trait, method), behaviours) in self.given_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                let key = (requested_trait, method, BehaviourKind::Given, behaviour.stmt_id);
+                behaviour.num_matches.set(counts.num_matches.get(&key).cloned().unwrap_or(0));
+            }
+        }
+        for (&(requested_trait, method), behaviours) in self.expect_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                let key = (requested_trait, method, BehaviourKind::Expect, behaviour.stmt_id);
+                behaviour.num_matches.set(counts.num_matches.get(&key).cloned().unwrap_or(0));
+            }
+        }
+        *self.shared().call_counts.borrow_mut() = counts.call_counts.iter().map(|(&key, &count)| (key, count)).collect();
+    }
+
+    /// Verifies that every *given* behaviour backed by a value sequence returned all of its values.
+    ///
+    /// The error lists each behaviour with its number of consumed and available values,
+    /// see `GivenBehaviour::with_sequence_len()`.
+    pub fn verify_sequences_consumed(&self) -> Result<(), VerificationError> {
+        let failures: Vec<String> = self.given_behaviours
+            .borrow()
+            .values()
+            .flatten()
+            .filter(|b| b.is_enabled() && !b.sequence_fully_consumed())
+            .map(|b| {
+                format!("Given behaviour consumed {} of {} sequence values: {}",
+                        b.num_matches.get(),
+                        b.sequence_len.unwrap_or(0),
+                        b.describe())
+            })
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(VerificationError::new(failures))
+        }
+    }
+
+    /// Verifies that none of the mock's behaviours, neither *given* nor *expected*, has been matched.
+    ///
+    /// Unlike `verify()` no expectations need to be set; the error lists every method which has been called.
+    pub fn verify_no_interactions(&self) -> Result<(), VerificationError> {
+        let mut called = Vec::new();
+        for (&(requested_trait, method), behaviours) in self.given_behaviours.borrow().iter() {
+            if behaviours.iter().any(|b| b.has_matched()) {
+                called.push((requested_trait, method));
+            }
+        }
+        for (&(requested_trait, method), behaviours) in self.expect_behaviours.borrow().iter() {
+            if behaviours.iter().any(|b| b.has_matched()) {
+                called.push((requested_trait, method));
+            }
+        }
+        if called.is_empty() {
+            return Ok(());
+        }
+
+        called.sort();
+        called.dedup();
+        Err(VerificationError::new(called.into_iter()
+            .map(|(requested_trait, method)| format!("Unexpected interaction with {}::{}", requested_trait, method))
+            .collect()))
+    }
+
+    /// Sets how a *given* behaviour is selected if several of them match a call.
+    ///
+    /// The default strategy is `SelectionStrategy::FirstMatch`.
+    /// Setting `SelectionStrategy::WeightedRandom` restarts the random number generator with the strategy's seed.
+    pub fn set_selection_strategy(&mut self, strategy: SelectionStrategy) {
+        if let SelectionStrategy::WeightedRandom(seed) = strategy {
+            self.rng_state.set(seed);
+        }
+        self.selection_strategy = strategy;
+    }
+
+    /// Makes `name` refer to the *expected* behaviour addressed by `target`, in addition to the behaviour's label.
+    ///
+    /// The behaviour is addressed by its trait, method, and `stmt_id` like the members of an `ExpectGroup`.
+    /// Ordering constraints, see `ExpectBehaviour::with_ordering()`, resolve their labels through the aliases,
+    /// so a behaviour can take part in several relationships under different names.
+    /// Aliasing a name again replaces its previous target, which is returned.
+    pub fn alias(&self, name: &str, target: (&'static str, &'static str, usize))
+                 -> Option<(&'static str, &'static str, usize)> {
+        self.aliases.borrow_mut().insert(name.to_string(), target)
+    }
+
+    /// Returns the trait, method, and `stmt_id` the alias `name` refers to, if any.
+    pub fn resolve_alias(&self, name: &str) -> Option<(&'static str, &'static str, usize)> {
+        self.aliases.borrow().get(name).cloned()
+    }
+
+    /// Sets how *expected* behaviours count a call which several of them match.
+    ///
+    /// The default mode is `ExpectMatchMode::AllMatching`. See `set_expect_match_mode_for()` to override it for a method.
+    pub fn set_expect_match_mode(&mut self, mode: ExpectMatchMode) {
+        self.expect_match_mode = mode;
+    }
+
+    /// Sets how the *expected* behaviours of the trait's method count a call which several of them match.
+    ///
+    /// The mode takes precedence over the one set by `set_expect_match_mode()`.
+    pub fn set_expect_match_mode_for(&mut self,
+                                     requested_trait: &'static str,
+                                     method: &'static str,
+                                     mode: ExpectMatchMode) {
+        self.expect_match_modes.insert((requested_trait, method), mode);
+    }
+
+    /// Returns how the *expected* behaviours of the trait's method count a call which several of them match.
+    pub fn expect_match_mode(&self, requested_trait: &'static str, method: &'static str) -> ExpectMatchMode {
+        self.expect_match_modes.get(&(requested_trait, method)).cloned().unwrap_or(self.expect_match_mode)
+    }
+
+    /// Notifies the *expected* behaviours of the trait's method which match a call according to the method's match mode.
+    ///
+    /// Disabled behaviours, behaviours which don't apply to the call, and behaviours rejected by `candidates_filter`
+    /// are not considered, the filter is usually the behaviour's `ArgMatcher` applied to the call's arguments.
+    /// Returns the number of notified behaviours.
+    pub fn match_expected<F>(&self, requested_trait: &'static str, method: &'static str, candidates_filter: F) -> usize
+        where F: Fn(&ExpectBehaviour) -> bool
+    {
+        let mode = self.expect_match_mode(requested_trait, method);
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let candidates = expect_behaviours.get(&(requested_trait, method))
+                                          .into_iter()
+                                          .flatten()
+                                          .filter(|b| b.is_enabled() && b.is_applicable() && candidates_filter(b));
+        let matched: Vec<&ExpectBehaviour> = match mode {
+            ExpectMatchMode::AllMatching => candidates.collect(),
+            ExpectMatchMode::FirstMatching => candidates.take(1).collect(),
+        };
+        for behaviour in matched.iter() {
+            behaviour.matched();
+        }
+        matched.len()
+    }
+
+    /// Sets a callback which is invoked the first time the *expected* behaviour with `stmt_id` becomes saturated.
+    ///
+    /// The callback fires exactly once, inside the match which saturates the behaviour, e.g., to release a latch.
+    /// If the behaviour is already saturated, e.g., as its minimum is `0`, the callback fires right away instead.
+    /// It must not add behaviours to the mock as the behaviours are borrowed while it runs.
+    /// Returns `false` if no such behaviour is registered for the trait's method.
+    pub fn set_on_satisfied(&self,
+                            requested_trait: &'static str,
+                            method: &'static str,
+                            stmt_id: usize,
+                            callback: Box<dyn FnMut()>)
+                            -> bool {
+        let expect_behaviours = self.expect_behaviours.borrow();
+        let behaviour = expect_behaviours.get(&(requested_trait, method))
+                                         .and_then(|behaviours| behaviours.iter().find(|b| b.stmt_id == stmt_id));
+        match behaviour {
+            Some(behaviour) => {
+                if behaviour.is_saturated() {
+                    let mut callback = callback;
+                    callback();
+                } else {
+                    *behaviour.on_satisfied.borrow_mut() = Some(callback);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the order in which the failures and warnings of *expected* behaviours are reported by the verification.
+    ///
+    /// Messages about groups, phases, ordering constraints, and *given* behaviours follow in a fixed order.
+    /// The default ordering is `ReportOrdering::RegistrationOrder`.
+    pub fn set_report_ordering(&mut self, ordering: ReportOrdering) {
+        self.report_ordering = ordering;
+    }
+
+    /// Selects the *given* behaviour of a trait's method which should handle a call according to the selection strategy.
+    ///
+    /// The candidates are looked up by `given_behaviours_for()`.
+    /// Disabled, exhausted, and expired behaviours, behaviours whose precondition does not hold,
+    /// and behaviours rejected by `candidates_filter` are not considered,
+    /// the filter is usually the behaviour's `ArgMatcher` applied to the call's arguments.
+    /// Returns `None` if no candidate is left.
+    ///
+    /// The returned reference borrows the mock's given behaviours, adding new ones while it is alive panics.
+    pub fn select_given<F>(&self,
+                           requested_trait: &'static str,
+                           method: &'static str,
+                           candidates_filter: F)
+                           -> Option<Ref<'_, GivenBehaviour>>
+        where F: Fn(&GivenBehaviour) -> bool
+    {
+        let strategy = self.selection_strategy;
+        let behaviours = self.given_behaviours_for(requested_trait, method)?;
+        Ref::filter_map(behaviours, |behaviours| {
+            let mut candidates = behaviours.iter().filter(|b| b.is_selectable() && candidates_filter(b));
+            match strategy {
+                SelectionStrategy::FirstMatch => candidates.next(),
+                SelectionStrategy::LastMatch => {
+                    let candidates: Vec<&GivenBehaviour> = candidates.collect();
+                    candidates.iter()
+                              .filter(|b| b.last_sequence_number.get().is_some())
+                              .max_by_key(|b| b.last_sequence_number.get())
+                              .or_else(|| candidates.first())
+                              .cloned()
+                }
+                SelectionStrategy::MostRecentlyAdded => candidates.last(),
+                SelectionStrategy::LeastUsed => candidates.min_by_key(|b| b.num_matches.get()),
+                SelectionStrategy::WeightedRandom(_) => {
+                    let candidates: Vec<&GivenBehaviour> = candidates.filter(|b| b.weight > 0).collect();
+                    let total_weight: u64 = candidates.iter().map(|b| u64::from(b.weight)).sum();
+                    if total_weight == 0 {
+                        return None;
+                    }
+                    let mut pick = next_random(&self.rng_state) % total_weight;
+                    candidates.into_iter().find(|b| {
+                        let weight = u64::from(b.weight);
+                        if pick < weight {
+                            true
+                        } else {
+                            pick -= weight;
+                            false
+                        }
+                    })
+                }
+            }
+        }).ok()
+    }
+
+    /// Looks up a behaviour by its `stmt_id`.
+    ///
+    /// The *given* behaviours are searched before the *expected* ones.
+    /// As ids are only unique within a mocked method and between the behaviours of the same kind,
+    /// the id of a given behaviour shadows an expected behaviour with the same id.
+    /// If several methods have a behaviour with the same id it is unspecified which one is returned.
+    ///
+    /// The returned reference borrows the mock's behaviours, adding new behaviours while it is alive panics.
+    pub fn behaviour_by_id(&self, stmt_id: usize) -> Option<BehaviourRef<'_>> {
+        if let Ok(behaviour) = Ref::filter_map(self.given_behaviours.borrow(), |behaviours| {
+            behaviours.values().flatten().find(|b| b.stmt_id == stmt_id)
+        }) {
+            return Some(BehaviourRef::Given(behaviour));
+        }
+        Ref::filter_map(self.expect_behaviours.borrow(), |behaviours| {
+            behaviours.values().flatten().find(|b| b.stmt_id == stmt_id)
+        }).ok().map(BehaviourRef::Expect)
+    }
+
+    /// Returns the description of the trait's method's behaviour with `stmt_id`, see `GivenBehaviour::describe()`.
+    ///
+    /// As *given* and *expected* behaviours are numbered independently, the given behaviour takes precedence
+    /// if both kinds have a behaviour with the id. Returns `None` if the method has no such behaviour.
+    pub fn repr_of(&self, requested_trait: &'static str, method: &'static str, stmt_id: usize) -> Option<String> {
+        let key = (requested_trait, method);
+        let given = self.given_behaviours
+                        .borrow()
+                        .get(&key)
+                        .and_then(|behaviours| behaviours.iter().find(|b| b.stmt_id == stmt_id))
+                        .map(|b| b.describe().to_string());
+        given.or_else(|| {
+            self.expect_behaviours
+                .borrow()
+                .get(&key)
+                .and_then(|behaviours| behaviours.iter().find(|b| b.stmt_id == stmt_id))
+                .map(|b| b.describe().to_string())
+        })
+    }
+
+    /// Appends the behaviours of `other` to the behaviours of this mock state.
+    ///
+    /// The behaviours of `other` are added under the same trait/method keys after the already present behaviours.
+    /// As behaviours are checked in the order they were added, the behaviours of `self` take precedence over the merged ones.
+    /// The match counts of `other`'s behaviours do not carry over, i.e., the merged behaviours start unmatched.
+    pub fn merge_from(&mut self, other: &MockState) {
+        for (key, behaviours) in other.given_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                self.add_given_behaviour(key.0, key.1, behaviour.unmatched_copy());
+            }
+        }
+        for (key, behaviours) in other.expect_behaviours.borrow().iter() {
+            for behaviour in behaviours {
+                if *key == SPANNING_KEY {
+                    self.add_spanning_expect_behaviour(behaviour.unmatched_copy());
+                } else {
+                    self.add_expect_behaviour(key.0, key.1, behaviour.unmatched_copy());
+                }
+            }
+        }
+    }
+
+    /// Creates an independent mock state with the behaviours and verification policies of this one.
+    ///
+    /// The behaviours of the fork start unmatched and share their bound variables with the original behaviours.
+    /// The fork shares the clock, the attached `SequenceClock`, and the default type matchers with the original,
+    /// e.g., advancing a `ManualClock` affects both. The fork's own counter of sequence numbers starts at `0`.
+    /// The message sink, callbacks, report filter, user data, and spy target are not copied,
+    /// neither is the fork frozen. This allows to configure a mock once and to fork it for each case of a parameterized test.
+    pub fn fork(&self) -> MockState {
+        let mut fork = MockState::new();
+        if let Some(shared) = self.shared.get() {
+            *fork.shared().clock.borrow_mut() = shared.clock.borrow().clone();
+            *fork.shared().sequence_clock.borrow_mut() = shared.sequence_clock.borrow().clone();
+        }
+        fork.type_matchers = self.type_matchers.clone();
+        fork.merge_from(self);
+        *fork.expect_groups.borrow_mut() = self.expect_groups.borrow().clone();
+        fork.verify_on_drop = self.verify_on_drop;
+        fork.verify_no_unused_given = self.verify_no_unused_given;
+        fork.log_calls = self.log_calls;
+        fork.treat_at_most_as_nonfatal = self.treat_at_most_as_nonfatal;
+        fork.set_selection_strategy(self.selection_strategy);
+        fork.warn_on_fully_unused_methods = self.warn_on_fully_unused_methods;
+        fork.known_methods = self.known_methods.clone();
+        fork.deduplicate_expectations = self.deduplicate_expectations;
+        fork.print_on_verify = self.print_on_verify;
+        fork.panic_on_verify = self.panic_on_verify;
+        fork.require_explicit_verify = self.require_explicit_verify;
+        fork.report_ordering = self.report_ordering;
+        fork.arg_transforms = self.arg_transforms.clone();
+        fork.in_order_methods = self.in_order_methods.clone();
+        fork.max_reported_failures = self.max_reported_failures;
+        *fork.aliases.borrow_mut() = self.aliases.borrow().clone();
+        fork.expect_match_mode = self.expect_match_mode;
+        fork.expect_match_modes = self.expect_match_modes.clone();
+        fork
+    }
+
+    /// Creates a fork of this mock, see `fork()`, whose matches are also counted by the shared `stats`.
+    ///
+    /// Passing the same statistics to each fork of a parameterized test sums up the matches across all cases.
+    pub fn fork_with_shared_stats(&self, stats: &std::rc::Rc<RefCell<AggregateStats>>) -> MockState {
+        let fork = self.fork();
+        *fork.shared().aggregate_stats.borrow_mut() = Some(stats.clone());
+        fork
+    }
+}
+
+impl Default for MockState {
+    /// Creates a new mock state, see `MockState::new()`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A group of *expected* behaviours which is satisfied if a quorum of its members is satisfied.
+///
+/// The members are addressed by the names of their mocked *trait* and *method* and their `stmt_id`.
+/// Members which are not registered with the mock count as unsatisfied.
+/// See `MockState::add_expect_group()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectGroup {
+    /// The members of the group.
+    members: Vec<(&'static str, &'static str, usize)>,
+    /// The minimum number of satisfied members for the group to be satisfied.
+    quorum: usize,
+}
+
+impl ExpectGroup {
+    /// Creates a group which is satisfied if any of its `members` is satisfied.
+    pub fn any_of(members: Vec<(&'static str, &'static str, usize)>) -> Self {
+        Self::at_least(1, members)
+    }
+
+    /// Creates a group which is satisfied if at least `quorum` of its `members` are satisfied.
+    pub fn at_least(quorum: usize, members: Vec<(&'static str, &'static str, usize)>) -> Self {
+        Self { members, quorum }
+    }
+
+    /// Returns `true` iff the behaviour with `stmt_id` of the trait's method is a member of the group.
+    pub fn contains(&self, requested_trait: &'static str, method: &'static str, stmt_id: usize) -> bool {
+        self.members.contains(&(requested_trait, method, stmt_id))
+    }
+
+    /// Returns the minimum number of satisfied members for the group to be satisfied.
+    pub fn quorum(&self) -> usize {
+        self.quorum
+    }
+}
+
+/// Distinguishes the behaviours defined in `given!`-blocks from the ones defined in `expect_interactions!`-blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BehaviourKind {
+    /// A *given* behaviour.
+    Given,
+    /// An *expected* behaviour.
+    Expect,
+}
+
+/// A match of a behaviour recorded in the invocation timeline of a mock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationRecord {
+    /// The position of the match in the timeline.
+    ///
+    /// The sequence numbers of a mock are drawn from its own counter, so they order the matches of that mock only.
+    /// The numbers of different mocks can only be compared if the mocks are attached to the same `SequenceClock`,
+    /// see `MockState::attach_sequence_clock()`.
+    pub sequence_number: usize,
+    /// The mocked trait's name.
+    pub requested_trait: &'static str,
+    /// The mocked trait's method's name.
+    pub method: &'static str,
+    /// The id of the matched behaviour.
+    pub stmt_id: usize,
+    /// Whether a *given* or an *expected* behaviour has been matched.
+    pub kind: BehaviourKind,
+}
+
+/// Checks that all matches of `earlier_method` on the `earlier` mock occurred before any match of `later_method` on the `later` mock.
+///
+/// Both methods are addressed by the names of their *trait* and *method*.
+/// Returns an error if the order is violated or if one of the methods has never been matched.
+pub fn assert_called_before(earlier: &dyn MockControl,
+                            earlier_method: (&'static str, &'static str),
+                            later: &dyn MockControl,
+                            later_method: (&'static str, &'static str))
+                            -> Result<(), String> {
+    let last_earlier = earlier.invocation_timeline()
+                              .iter()
+                              .filter(|record| (record.requested_trait, record.method) == earlier_method)
+                              .map(|record| record.sequence_number)
+                              .max();
+    let first_later = later.invocation_timeline()
+                           .iter()
+                           .filter(|record| (record.requested_trait, record.method) == later_method)
+                           .map(|record| record.sequence_number)
+                           .min();
+    match (last_earlier, first_later) {
+        (None, _) => Err(format!("{}::{} has never been called", earlier_method.0, earlier_method.1)),
+        (_, None) => Err(format!("{}::{} has never been called", later_method.0, later_method.1)),
+        (Some(last_earlier), Some(first_later)) if last_earlier > first_later => {
+            Err(format!("{}::{} has been called after {}::{}",
+                        earlier_method.0,
+                        earlier_method.1,
+                        later_method.0,
+                        later_method.1))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The relation between the match counts of two methods required by `assert_count_relation()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountRelation {
+    /// The first method is matched less often than the second.
+    Less,
+    /// The first method is matched at most as often as the second.
+    LessOrEqual,
+    /// Both methods are matched equally often.
+    Equal,
+    /// The first method is matched at least as often as the second.
+    GreaterOrEqual,
+    /// The first method is matched more often than the second.
+    Greater,
+}
+
+impl CountRelation {
+    /// Returns `true` iff `count_a` and `count_b` are in the relation.
+    pub fn holds(&self, count_a: usize, count_b: usize) -> bool {
+        match *self {
+            CountRelation::Less => count_a < count_b,
+            CountRelation::LessOrEqual => count_a <= count_b,
+            CountRelation::Equal => count_a == count_b,
+            CountRelation::GreaterOrEqual => count_a >= count_b,
+            CountRelation::Greater => count_a > count_b,
+        }
+    }
+}
+
+/// Checks that the summed matches of `method_a` compare to the summed matches of `method_b` as `relation` requires.
+///
+/// Both methods are addressed by the names of their *trait* and *method*, their matches are the matches
+/// of all *given* and *expected* behaviours of the method. Methods without behaviours count as never matched.
+/// E.g., `CountRelation::GreaterOrEqual` requires `method_a` to be matched at least as often as `method_b`.
+pub fn assert_count_relation(mock: &MockState,
+                             method_a: (&'static str, &'static str),
+                             relation: CountRelation,
+                             method_b: (&'static str, &'static str))
+                             -> Result<(), String> {
+    let count_a = mock.num_matches_of(method_a);
+    let count_b = mock.num_matches_of(method_b);
+    if relation.holds(count_a, count_b) {
+        return Ok(());
+    }
+    let expected = match relation {
+        CountRelation::Less => "less often than",
+        CountRelation::LessOrEqual => "at most as often as",
+        CountRelation::Equal => "as often as",
+        CountRelation::GreaterOrEqual => "at least as often as",
+        CountRelation::Greater => "more often than",
+    };
+    Err(format!("{}::{} has been called {} times, expected {} {}::{} which has been called {} times",
+                method_a.0,
+                method_a.1,
+                count_a,
+                expected,
+                method_b.0,
+                method_b.1,
+                count_b))
+}
+
+/// A call of a mocked trait's method recorded by `MockState::log_call()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallLogEntry {
+    /// The trait's name.
+    pub requested_trait: &'static str,
+    /// The trait's method's name.
+    pub method: &'static str,
+    /// The `Debug` representation of the call's arguments.
+    pub args_repr: String,
+}
+
+/// An owned summary of the state of an *expected* behaviour, see `MockState::expect_behaviours_where()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct BehaviourSummary {
+    /// The mocked trait's name.
+    pub requested_trait: &'static str,
+    /// The mocked trait's method's name.
+    pub method: &'static str,
+    /// The id of the behaviour.
+    pub stmt_id: usize,
+    /// How often the behaviour has been matched.
+    pub num_matches: usize,
+    /// The expected minimum number of matches, if any.
+    pub expected_min_matches: Option<usize>,
+    /// The expected maximum number of matches, if any.
+    pub expected_max_matches: Option<usize>,
+    /// Whether the behaviour is satisfied.
+    pub is_saturated: bool,
+    /// The behaviour's description, see `ExpectBehaviour::describe()`.
+    pub description: String,
+}
+
+/// A call of a mocked trait's method which matched no behaviour, recorded by `MockState::record_unexpected_call()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedCall {
+    /// The trait's name.
+    pub requested_trait: &'static str,
+    /// The trait's method's name.
+    pub method: &'static str,
+    /// The behaviours which have been tried but rejected the call.
+    pub candidates: Vec<TriedCandidate>,
+}
+
+/// A behaviour which has been tried for an unexpected call but rejected it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriedCandidate {
+    /// The id of the behaviour.
+    pub stmt_id: usize,
+    /// The string representation of the behaviour's definition.
+    pub stmt_repr: String,
+    /// Why the behaviour's matcher rejected the call's arguments, if known.
+    pub explanation: Option<String>,
+}
+
+/// A borrowed *given* or *expected* behaviour of a `MockState`.
+pub enum BehaviourRef<'a> {
+    /// A behaviour defined in a `given!`-block.
+    Given(Ref<'a, GivenBehaviour>),
+    /// A behaviour defined in an `expect_interactions!`-block.
+    Expect(Ref<'a, ExpectBehaviour>),
+}
+
+impl<'a> BehaviourRef<'a> {
+    /// Returns the unique id of the behaviour within the mocked method to which it belongs.
+    pub fn stmt_id(&self) -> usize {
+        match *self {
+            BehaviourRef::Given(ref behaviour) => behaviour.stmt_id,
+            BehaviourRef::Expect(ref behaviour) => behaviour.stmt_id,
+        }
+    }
+
+    /// Returns `true` iff the behaviour has been matched at least once.
+    pub fn has_matched(&self) -> bool {
+        match *self {
+            BehaviourRef::Given(ref behaviour) => behaviour.has_matched(),
+            BehaviourRef::Expect(ref behaviour) => behaviour.has_matched(),
+        }
+    }
+
+    /// Returns a description of the behaviour.
+    pub fn describe(&self) -> &str {
+        match *self {
+            BehaviourRef::Given(ref behaviour) => behaviour.describe(),
+            BehaviourRef::Expect(ref behaviour) => behaviour.describe(),
+        }
+    }
+}
+
+impl MockControl for MockState {
+    fn should_verify_on_drop(&mut self, flag: bool) {
+        self.verify_on_drop = flag;
+    }
+
+    fn add_given_behaviour(&self,
+                           requested_trait: &'static str,
+                           method: &'static str,
+                           mut behaviour: GivenBehaviour) {
+        self.assert_accepts_behaviours(requested_trait, method);
+        if cfg!(feature = "disabled") {
+            return;
+        }
+        debug_assert!(self.is_known_method(requested_trait, method),
+                      "Behaviour added for the unknown method {}::{}: {}",
+                      requested_trait,
+                      method,
+                      behaviour.describe());
+        behaviour.registration = Some(self.registration_for(requested_trait, method));
+        self.given_behaviours
+            .borrow_mut()
+            .entry((requested_trait, method))
+            .or_default()
+            .push(behaviour);
+    }
+
+    fn reset_given_behaviours(&mut self) {
+        self.given_behaviours.borrow_mut().clear();
+    }
+
+    fn add_expect_behaviour(&self,
+                            requested_trait: &'static str,
+                            method: &'static str,
+                            behaviour: ExpectBehaviour) {
+        if self.deduplicate_expectations {
+            self.add_expect_behaviour_deduplicated(requested_trait, method, behaviour);
+        } else {
+            self.push_expect_behaviour(requested_trait, method, behaviour);
+        }
+    }
+
+    fn reset_expected_behaviours(&mut self) {
+        self.expect_behaviours.borrow_mut().clear();
+        self.expect_groups.borrow_mut().clear();
+    }
+
+    fn are_expected_behaviours_satisfied(&self) -> bool {
+        let report = self.verification_report();
+        self.send_to_sink(&report);
+        report.is_satisfied()
+    }
+
+    fn verify(&self) {
+        if std::thread::panicking() {
+            return;
+        }
+        self.explicitly_verified.set(true);
+        let _verifying = self.enter_verification();
+        let report = self.verification_report();
+        if self.print_on_verify {
+            self.send_to_sink(&report);
+        }
+        let is_satisfied = report.is_satisfied();
+        if !is_satisfied {
+            if let Some(ref mut callback) = *self.on_verification_failure.borrow_mut() {
+                callback(&report);
+            }
+        }
+        *self.last_report.borrow_mut() = Some(report);
+        if !is_satisfied {
+            if self.panic_on_verify {
+                panic!("There are unsatisfied expected behaviours for mocked traits.");
+            }
+            return;
+        }
+        self.verified.set(true);
+    }
+
+    fn interaction_summary(&self) -> String {
+        let given_behaviours = self.given_behaviours.borrow();
+        let expect_behaviours = self.expect_behaviours.borrow();
+
+        let mut keys: Vec<&(&'static str, &'static str)> = given_behaviours.keys()
+                                                                           .chain(expect_behaviours.keys())
+                                                                           .collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut entries = Vec::new();
+        for key in keys {
+            let expected = expect_behaviours.get(key).map(|vs| vs.as_slice()).unwrap_or(&[]);
+            // a call matches at most one given behaviour so their sum is the best estimate for the calls
+            let given_matches: usize = given_behaviours.get(key)
+                                                       .map(|vs| vs.iter().map(|b| b.num_matches.get()).sum())
+                                                       .unwrap_or(0);
+            let calls = if given_matches > 0 {
+                given_matches
+            } else {
+                expected.iter().map(|b| b.num_matches.get()).sum()
+            };
+
+            let mut entry = format!("{}::{} x{}", key.0, key.1, calls);
+            if !expected.is_empty() {
+                let repetitions: Vec<String> = expected.iter().map(|b| b.expected_repetitions()).collect();
+                let status = if expected.iter().all(|b| b.is_saturated()) { "OK" } else { "FAILED" };
+                entry.push_str(&format!(" (expected {}, {})", repetitions.join(" and "), status));
+            }
+            entries.push(entry);
+        }
+        entries.join(", ")
+    }
+
+    fn describe_configuration(&self) -> String {
+        let given_behaviours = self.given_behaviours.borrow();
+        let expect_behaviours = self.expect_behaviours.borrow();
+
+        let mut keys: Vec<&(&'static str, &'static str)> = given_behaviours.keys()
+                                                                           .chain(expect_behaviours.keys())
+                                                                           .collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut lines = Vec::new();
+        for key in keys {
+            let given = given_behaviours.get(key).map(|vs| vs.as_slice()).unwrap_or(&[]);
+            let expected = expect_behaviours.get(key).map(|vs| vs.as_slice()).unwrap_or(&[]);
+            lines.push(format!("{}::{}: {} given, {} expected", key.0, key.1, given.len(), expected.len()));
+            for behaviour in given {
+                lines.push(format!("  given #{}: {}", behaviour.stmt_id, behaviour.describe()));
+            }
+            for behaviour in expected {
+                lines.push(format!("  expect #{}: {} ({})",
+                                   behaviour.stmt_id,
+                                   behaviour.describe(),
+                                   behaviour.expected_repetitions()));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn total_calls(&self) -> usize {
+        let given_matches: usize = self.given_behaviours
+                                       .borrow()
+                                       .values()
+                                       .flatten()
+                                       .map(|b| b.num_matches.get())
+                                       .sum();
+        let expect_matches: usize = self.expect_behaviours
+                                        .borrow()
+                                        .values()
+                                        .flatten()
+                                        .map(|b| b.num_matches.get())
+                                        .sum();
+        given_matches + expect_matches
+    }
+
+    fn total_calls_for_trait(&self, requested_trait: &'static str) -> usize {
+        let given_matches: usize = self.given_behaviours
+                                       .borrow()
+                                       .iter()
+                                       .filter(|&(key, _)| key.0 == requested_trait)
+                                       .flat_map(|(_, behaviours)| behaviours)
+                                       .map(|b| b.num_matches.get())
+                                       .sum();
+        let expect_matches: usize = self.expect_behaviours
+                                        .borrow()
+                                        .iter()
+                                        .filter(|&(key, _)| key.0 == requested_trait)
+                                        .flat_map(|(_, behaviours)| behaviours)
+                                        .map(|b| b.num_matches.get())
+                                        .sum();
+        given_matches + expect_matches
+    }
+
+    fn invocation_timeline(&self) -> Vec<InvocationRecord> {
+        self.shared.get().map_or_else(Vec::new, |shared| shared.timeline.borrow().iter().cloned().collect())
+    }
+
+    fn count_call(&self, requested_trait: &'static str, method: &'static str) {
+        self.record_call(requested_trait, method);
+    }
+}
+
+impl std::ops::Drop for MockState {
+    /// Verfies the *expected interactions* on the mock if the policy is enabled.
+    ///
+    /// The verification is skipped if `verify()` has already been called explicitly and succeeded.
+    /// Interactions occurring after the successful explicit verification are therefore not checked again.
+    /// A failed explicit verification panics, the mock is then dropped while unwinding and thus not verified again.
+    ///
+    /// # Panics
+    /// iff the verification fails or if explicit verification is required but the mock has never been verified explicitly.
+    fn drop(&mut self) {
+        if self.require_explicit_verify
+           && !self.explicitly_verified.get()
+           && !std::thread::panicking()
+           && self.expect_behaviours.borrow().values().any(|behaviours| !behaviours.is_empty()) {
+            panic!("mock dropped without explicit verification");
+        }
+        if self.verify_on_drop && !self.verified.get() {
+            self.verify();
+        }
+    }
+}
+
+
+/// Defines a matcher for the arguments of a mocked method.
+///
+/// A matcher may check a single argument of a method or all arguments at once.
+/// If all arguments are to be checked then they should be passed in a curried form, e.g., as a tuple.
+pub trait ArgMatcher<'a, T: 'a> {
+    // Returns `true` iff the `actual` arguments satisfy the matcher.
+    fn match_args(&self, actual: &'a T) -> bool;
+
+    /// Returns a human-readable description of the matcher used in diagnostic messages.
+    ///
+    /// The static `stmt_repr` of a behaviour only shows the matcher's source expression.
+    /// Matchers which know more about their own expectation, e.g., the forbidden value of a negated matcher, should override the method.
+    fn describe_matcher(&self) -> String {
+        "a custom matcher".to_string()
+    }
+
+    /// Returns an explanation why the `actual` arguments are rejected by the matcher, e.g., "expected X, got Y".
+    ///
+    /// Returns `None` if the arguments satisfy the matcher or if the matcher is unable to explain the rejection.
+    /// The explanations can be collected by the dispatch code for diagnosing calls which match no behaviour.
+    fn explain(&self, _actual: &'a T) -> Option<String> {
+        None
+    }
+
+    /// Returns `true` iff the `actual` arguments passed by value satisfy the matcher.
+    ///
+    /// The default borrows the arguments and delegates to `match_args`.
+    /// Matchers which need to keep the arguments, e.g., to capture them, should override the method to avoid a clone.
+    fn match_args_owned(&self, actual: T) -> bool
+        where Self: BorrowingArgMatcher<T>
+    {
+        self.match_borrowed_args(&actual)
+    }
+}
+
+/// An `ArgMatcher` which accepts arguments borrowed for any lifetime.
+///
+/// The trait is implemented for all such matchers and enables the default of `ArgMatcher::match_args_owned`.
+pub trait BorrowingArgMatcher<T> {
+    /// Returns `true` iff the borrowed `actual` arguments satisfy the matcher.
+    fn match_borrowed_args(&self, actual: &T) -> bool;
+}
+
+impl<T, M: ?Sized> BorrowingArgMatcher<T> for M
+    where M: for<'b> ArgMatcher<'b, T>
+{
+    fn match_borrowed_args(&self, actual: &T) -> bool {
+        self.match_args(actual)
+    }
+}
+
+/// An argument matcher which is not tied to the lifetime of the arguments' borrow.
+///
+/// Unlike an `ArgMatcher<'a, T>` an owned matcher can be stored and applied to the arguments of any later call,
+/// e.g., by behaviours which persist across calls or by capturing and stateful matchers.
+///
+/// Every `'static` matcher which implements `ArgMatcher<'a, T>` for *all* lifetimes `'a` is an owned matcher. This includes
+/// * closures accepting a reference to the arguments which capture no borrowed data,
+/// * the matchers of the `matchers` module if their values and inner matchers are `'static`.
+///
+/// The following cannot be promoted:
+/// * closures capturing references, their matcher is bound to the lifetime of the captured borrow,
+/// * the matchers of **galvanic-assert** as `Box<Matcher<'a, T> + 'a>` is bound to a single lifetime `'a`,
+/// * matchers for arguments containing references, as the argument type `T` itself is not `'static`.
+pub trait OwnedArgMatcher<T>: 'static {
+    /// Returns `true` iff the `actual` arguments satisfy the matcher.
+    fn match_owned_args(&self, actual: &T) -> bool;
+}
+
+impl<T, M> OwnedArgMatcher<T> for M
+    where M: BorrowingArgMatcher<T> + 'static
+{
+    fn match_owned_args(&self, actual: &T) -> bool {
+        self.match_borrowed_args(actual)
+    }
+}
+
+/// Converts a matcher into an `OwnedArgMatcher` trait object which can be stored independently of the matcher's type.
+pub fn into_owned_matcher<T, M>(matcher: M) -> Box<dyn OwnedArgMatcher<T>>
+    where M: OwnedArgMatcher<T>
+{
+    Box::new(matcher)
+}
+
+/// A stored `OwnedArgMatcher` can be used as `ArgMatcher` for arguments borrowed for any lifetime.
+impl<'a, T: 'static> ArgMatcher<'a, T> for Box<dyn OwnedArgMatcher<T>> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        (**self).match_owned_args(actual)
+    }
+}
+
+/// Any function accepting an argument and returning a `bool` can be used as `ArgMatcher`.
+impl<'a, T: 'a, F> ArgMatcher<'a, T> for F
+    where F: Fn(&'a T) -> bool
+{
+    fn match_args(&self, actual: &'a T) -> bool {
+        self(actual)
+    }
+}
+
+/// All matchers of the **galvanic-assert** crate can be used as `ArgMatcher`.
+///
+/// The crate's matchers can either be used to inspect a single argument or all of them (in curried form).
+#[cfg(feature = "galvanic_assert_integration")]
+impl<'a, T: 'a> ArgMatcher<'a, T> for Box<dyn galvanic_assert::Matcher<'a, T> + 'a> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        self.check(actual).into()
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        match self.check(actual) {
+            galvanic_assert::MatchResult::Matched { .. } => None,
+            galvanic_assert::MatchResult::Failed { name, reason } => Some(format!("{}: {}", name, reason)),
+        }
+    }
+}
+
+/// Stores the state of a *given* behaviour.
+pub struct GivenBehaviour {
+    /// The unique id of the behaviour within the mocked method to which it belongs.
+    pub stmt_id: usize,
+    /// How often the behaviour has been matched.
+    num_matches: std::cell::Cell<usize>,
+    /// How often the behaviour should be matched before it is exhausted, `None` if never.
+    expected_matches: Option<usize>,
+    /// The sequence number of the behaviour's most recent match in the mock's invocation timeline.
+    last_sequence_number: std::cell::Cell<Option<usize>>,
+    /// The relative probability of the behaviour being selected by `SelectionStrategy::WeightedRandom`.
+    weight: u32,
+    /// Whether the behaviour is considered by the selection, see `set_enabled()`.
+    enabled: std::cell::Cell<bool>,
+    /// A condition which must hold for the behaviour to be selected.
+    precondition: Option<std::rc::Rc<dyn Fn() -> bool>>,
+    /// The number of canned values returned by the behaviour in sequence, if any.
+    sequence_len: Option<usize>,
+    /// How long after its registration the behaviour stops matching.
+    #[cfg(feature = "timing")]
+    ttl: Option<std::time::Duration>,
+    /// Compares the behaviour's bound variables with the ones of another behaviour.
+    bound_eq: Option<BoundEq>,
+    /// Renders the behaviour's bound variables for diagnostic messages.
+    bound_debug: Option<BoundDebug>,
+    /// Whether the behaviour's matcher accepts any arguments.
+    matches_any_args: bool,
+    /// The mock the behaviour is registered with.
+    registration: Option<Registration>,
+    /// The bound variables available to the behaviour's `ArgMatcher`.
+    pub bound: std::rc::Rc<dyn std::any::Any>,
+    /// A string representation of the behaviour's definition.
+    stmt_repr: String,
+    /// A human-readable description overriding `stmt_repr` in diagnostic messages.
+    description: Option<String>,
+}
+
+impl GivenBehaviour {
+    /// Creates a new behaviour which is exhausted after `expected_matches`, never if `None`.
+    fn with_limit(expected_matches: Option<usize>,
+                  stmt_id: usize,
+                  bound: std::rc::Rc<dyn std::any::Any>,
+                  stmt_repr: &str)
+                  -> Self {
+        Self {
+            stmt_id,
+            num_matches: std::cell::Cell::new(0),
+            expected_matches,
+            last_sequence_number: std::cell::Cell::new(None),
+            weight: 1,
+            enabled: std::cell::Cell::new(true),
+            precondition: None,
+            sequence_len: None,
+            #[cfg(feature = "timing")]
+            ttl: None,
+            bound_eq: None,
+            bound_debug: None,
+            matches_any_args: false,
+            registration: None,
+            bound,
+            stmt_repr: stmt_repr.to_string(),
+            description: None,
+        }
+    }
+
+    /// Creates a new behaviour which is never exhausted.
+    pub fn with(stmt_id: usize, bound: std::rc::Rc<dyn std::any::Any>, stmt_repr: &str) -> Self {
+        Self::with_limit(None, stmt_id, bound, stmt_repr)
+    }
+
+    /// Creates a new behaviour without bound variables which is never exhausted.
+    ///
+    /// This is the simplest stub, e.g., for methods without arguments or with a match-all pattern.
+    pub fn always(stmt_id: usize, stmt_repr: &str) -> Self {
+        Self::with(stmt_id, std::rc::Rc::new(()), stmt_repr).with_matches_any_args(true)
+    }
+
+    /// Declares whether the behaviour's matcher accepts any arguments.
+    ///
+    /// The library can't inspect the matchers, the declaration is used by `MockState::find_shadowed_behaviours()`.
+    pub fn with_matches_any_args(mut self, flag: bool) -> Self {
+        self.matches_any_args = flag;
+        self
+    }
+
+    /// Creates a new behaviour which is never exhausted after being matched `times`.
+    pub fn with_times(times: usize,
+                      stmt_id: usize,
+                      bound: std::rc::Rc<dyn std::any::Any>,
+                      stmt_repr: &str)
+                      -> Self {
+        Self::with_limit(Some(times), stmt_id, bound, stmt_repr)
+    }
+
+    /// Sets a condition which must hold for the behaviour to be selected by `MockState::select_given()`.
+    ///
+    /// The condition is evaluated for each call, e.g., it can check whether a method of another mock has been matched
+    /// to model a resource which must be opened before it can be used.
+    pub fn with_precondition(mut self, precondition: std::rc::Rc<dyn Fn() -> bool>) -> Self {
+        self.precondition = Some(precondition);
+        self
+    }
+
+    /// Sets how the behaviour's bound variables are rendered in verification reports, e.g., with their `Debug` representation.
+    pub fn with_bound_debug(mut self, bound_debug: BoundDebug) -> Self {
+        self.bound_debug = Some(bound_debug);
+        self
+    }
+
+    /// Returns the description of the behaviour followed by its rendered bound variables, if available.
+    ///
+    /// See `with_bound_debug()`.
+    pub fn describe_with_bound(&self) -> String {
+        match self.bound_debug {
+            Some(ref bound_debug) => format!("{} with bound {}", self.describe(), bound_debug()),
+            None => self.describe().to_string(),
+        }
+    }
+
+    /// Sets how the behaviour's bound variables are compared with the ones of another behaviour.
+    ///
+    /// Without a comparator bound variables are only equal if they are the same object, see `Rc::ptr_eq()`.
+    pub fn with_bound_eq(mut self, bound_eq: BoundEq) -> Self {
+        self.bound_eq = Some(bound_eq);
+        self
+    }
+
+    /// Returns `true` iff the bound variables of the behaviours are equal, see `with_bound_eq()`.
+    pub fn has_equal_bound(&self, other: &GivenBehaviour) -> bool {
+        bounds_equal(&self.bound_eq, &self.bound, &other.bound)
+    }
+
+    /// Returns `true` iff the behaviour has no precondition or its precondition holds.
+    pub fn is_precondition_met(&self) -> bool {
+        self.precondition.as_ref().is_none_or(|precondition| precondition())
+    }
+
+    /// Declares that the behaviour returns a sequence of `len` canned values, one per match.
+    ///
+    /// See `sequence_fully_consumed()`.
+    pub fn with_sequence_len(mut self, len: usize) -> Self {
+        self.sequence_len = Some(len);
+        self
+    }
+
+    /// Returns `true` iff the behaviour has been matched at least as often as its sequence has values.
+    ///
+    /// Behaviours without a sequence are always fully consumed.
+    pub fn sequence_fully_consumed(&self) -> bool {
+        self.sequence_len.is_none_or(|len| self.num_matches.get() >= len)
+    }
+
+    /// Lets the behaviour expire once `ttl` has elapsed since it has been registered with a mock.
+    ///
+    /// Expired behaviours are skipped by `MockState::select_given()`, e.g., to model session tokens or caches.
+    #[cfg(feature = "timing")]
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns `true` iff the behaviour's time to live has elapsed, see `with_ttl()`.
+    ///
+    /// Behaviours which are not registered with a mock never expire.
+    #[cfg(feature = "timing")]
+    pub fn is_expired(&self) -> bool {
+        match (self.ttl, self.registration.as_ref()) {
+            (Some(ttl), Some(registration)) => registration.shared.now() >= registration.registered_at + ttl,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` iff the behaviour has a time to live, see `with_ttl()`.
+    fn has_ttl(&self) -> bool {
+        #[cfg(feature = "timing")]
+        {
+            if self.ttl.is_some() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns `true` iff the behaviour may be selected by `MockState::select_given()` regardless of the call's arguments.
+    fn is_selectable(&self) -> bool {
+        #[cfg(feature = "timing")]
+        {
+            if self.is_expired() {
+                return false;
+            }
+        }
+        self.is_enabled() && !self.is_saturated() && self.is_precondition_met()
+    }
+
+    /// Sets the relative probability of the behaviour being selected by `SelectionStrategy::WeightedRandom`.
+    ///
+    /// The default weight is `1`, a behaviour with weight `0` is never selected by the strategy.
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Returns a copy of the behaviour's definition which has not been matched yet.
+    fn unmatched_copy(&self) -> Self {
+        Self {
+            stmt_id: self.stmt_id,
+            num_matches: std::cell::Cell::new(0),
+            expected_matches: self.expected_matches,
+            last_sequence_number: std::cell::Cell::new(None),
+            weight: self.weight,
+            enabled: std::cell::Cell::new(self.enabled.get()),
+            precondition: self.precondition.clone(),
+            sequence_len: self.sequence_len,
+            #[cfg(feature = "timing")]
+            ttl: self.ttl,
+            bound_eq: self.bound_eq.clone(),
+            bound_debug: self.bound_debug.clone(),
+            matches_any_args: self.matches_any_args,
+            registration: None,
+            bound: self.bound.clone(),
+            stmt_repr: self.stmt_repr.clone(),
+            description: self.description.clone(),
+        }
+    }
+
+    /// Enables or disables the behaviour without removing it from its mock.
+    ///
+    /// A disabled behaviour is skipped by `MockState::select_given()` and ignores its matches.
+    /// Behaviours are enabled by default.
+    pub fn set_enabled(&self, flag: bool) {
+        self.enabled.set(flag);
+    }
+
+    /// Returns `true` iff the behaviour is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Notifies the behaviour that it has been matched.
+    ///
+    /// Does nothing if the mock the behaviour is registered with does not record matches or if the behaviour is disabled.
+    pub fn matched(&self) {
+        if cfg!(feature = "disabled") || !is_recording(&self.registration) || !self.is_enabled() {
+            return;
+        }
+        self.num_matches.set(self.num_matches.get() + 1);
+        if let Some(ref registration) = self.registration {
+            let sequence_number = registration.record_match(self.stmt_id, BehaviourKind::Given);
+            self.last_sequence_number.set(Some(sequence_number));
+        }
+    }
+
+    /// Returns `true` iff the behaviour has been matched at least once.
+    pub fn has_matched(&self) -> bool {
+        self.num_matches.get() > 0
+    }
+
+    /// Returns `true` iff the behaviour is exhausted.
+    pub fn is_saturated(&self) -> bool {
+        match self.expected_matches {
+            Some(limit) => self.num_matches.get() >= limit,
+            None => false,
+        }
+    }
+
+    /// Returns a description of the behaviour.
+    ///
+    /// The description set by `with_description()` takes precedence over the behaviour's `stmt_repr`.
+    pub fn describe(&self) -> &str {
+        self.description.as_ref().unwrap_or(&self.stmt_repr)
+    }
+
+    /// Sets a human-readable description of the behaviour's intent which replaces its `stmt_repr` in diagnostic messages.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Returns the behaviour's bound variables as the concrete type `T`.
+    ///
+    /// Panics if the bound variables are not of type `T`, see `try_bound_as()`.
+    pub fn bound_as<T: 'static>(&self) -> &T {
+        downcast_bound(&self.bound, &self.stmt_repr)
+    }
+
+    /// Returns the behaviour's bound variables as the concrete type `T`.
+    ///
+    /// Fails with the expected and the actual `TypeId` if the bound variables are not of type `T`.
+    pub fn try_bound_as<T: 'static>(&self) -> Result<&T, BoundTypeMismatch> {
+        try_downcast_bound(&self.bound, &self.stmt_repr)
+    }
+}
+
+impl std::fmt::Display for GivenBehaviour {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[#{}] {} (matched {}x)", self.stmt_id, self.describe(), self.num_matches.get())
+    }
+}
+
+
+/// Compares the bound variables of two behaviours.
+pub type BoundEq = std::rc::Rc<dyn Fn(&dyn std::any::Any, &dyn std::any::Any) -> bool>;
+
+/// Renders the bound variables of a behaviour.
+pub type BoundDebug = std::rc::Rc<dyn Fn() -> String>;
+
+/// Maps the arguments of a call to the arguments seen by the matchers, see `MockState::set_arg_transform()`.
+pub type ArgTransform = std::rc::Rc<dyn Fn(std::rc::Rc<dyn std::any::Any>) -> std::rc::Rc<dyn std::any::Any>>;
+
+/// Returns `true` iff the bound variables are equal according to `bound_eq` or, without a comparator, are the same object.
+fn bounds_equal(bound_eq: &Option<BoundEq>,
+                bound: &std::rc::Rc<dyn std::any::Any>,
+                other: &std::rc::Rc<dyn std::any::Any>)
+                -> bool {
+    match *bound_eq {
+        Some(ref bound_eq) => bound_eq(&**bound, &**other),
+        None => std::rc::Rc::ptr_eq(bound, other),
+    }
+}
+
+/// Downcasts the bound variables of a behaviour, panicking with the behaviour's `stmt_repr` on a type mismatch.
+fn downcast_bound<'a, T: 'static>(bound: &'a std::rc::Rc<dyn std::any::Any>, stmt_repr: &str) -> &'a T {
+    try_downcast_bound(bound, stmt_repr).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Downcasts the bound variables of a behaviour, failing with the behaviour's `stmt_repr` on a type mismatch.
+fn try_downcast_bound<'a, T: 'static>(bound: &'a std::rc::Rc<dyn std::any::Any>,
+                                      stmt_repr: &str)
+                                      -> Result<&'a T, BoundTypeMismatch> {
+    bound.downcast_ref::<T>().ok_or_else(|| {
+        BoundTypeMismatch {
+            expected_type: std::any::TypeId::of::<T>(),
+            expected_type_name: std::any::type_name::<T>(),
+            actual_type: (**bound).type_id(),
+            stmt_repr: stmt_repr.to_string(),
+        }
+    })
+}
+
+/// The error returned if the bound variables of a behaviour are not of the requested type.
+///
+/// This usually indicates that the dispatch code passes a tuple of the wrong arity to the behaviour's matcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundTypeMismatch {
+    /// The `TypeId` of the requested type.
+    pub expected_type: std::any::TypeId,
+    /// The name of the requested type.
+    pub expected_type_name: &'static str,
+    /// The `TypeId` of the behaviour's bound variables.
+    pub actual_type: std::any::TypeId,
+    /// A string representation of the behaviour's definition.
+    pub stmt_repr: String,
+}
+
+impl std::fmt::Display for BoundTypeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,
+               "Bound variables are not of the type `{}` ({:?}) expected by the behaviour but of {:?}: {}",
+               self.expected_type_name,
+               self.expected_type,
+               self.actual_type,
+               self.stmt_repr)
+    }
+}
+
+impl std::error::Error for BoundTypeMismatch {}
+
+/// A violation of the constraint on the arguments an *expected* behaviour is matched with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgsViolation {
+    /// The index of the offending match of the behaviour, starting at `0`.
+    pub call_index: usize,
+    /// A description of the violation.
+    pub message: String,
+}
+
+/// A constraint on the order of an *expected* behaviour's matches relative to another behaviour referenced by its label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderingConstraint {
+    /// All matches must occur before the first match of the labelled behaviour.
+    Before(String),
+    /// All matches must occur after the last match of the labelled behaviour.
+    After(String),
+}
+
+impl OrderingConstraint {
+    /// Returns the label of the referenced behaviour.
+    pub fn label(&self) -> &str {
+        match *self {
+            OrderingConstraint::Before(ref label) | OrderingConstraint::After(ref label) => label,
+        }
+    }
+}
+
+/// The error returned if an expected behaviour is created with a minimum number of matches exceeding its maximum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCountRange {
+    /// The requested minimum number of matches.
+    pub at_least_times: usize,
+    /// The requested maximum number of matches.
+    pub at_most_times: usize,
+    /// A string representation of the rejected behaviour's definition.
+    pub stmt_repr: String,
+}
+
+impl std::fmt::Display for InvalidCountRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,
+               "Behaviour can never be satisfied as it expects at least {} but at most {} matches: {}",
+               self.at_least_times,
+               self.at_most_times,
+               self.stmt_repr)
+    }
+}
+
+impl std::error::Error for InvalidCountRange {}
+
+/// Stores the state of a *expected* behaviour.
+pub struct ExpectBehaviour {
+    /// The unique id of the behaviour within the mocked method to which it belongs.
+    pub stmt_id: usize,
+    /// How often the behaviour has been matched.
+    num_matches: std::cell::Cell<usize>,
+    /// The expected minimum number of matches for the behaviour to be satisfied
+    expected_min_matches: std::cell::Cell<Option<usize>>,
+    /// The expected maximum number of matches for the behaviour to be satisfied
+    expected_max_matches: std::cell::Cell<Option<usize>>,
+    /// A custom condition on the number of matches replacing the expected minimum and maximum.
+    count_predicate: Option<std::rc::Rc<dyn Fn(usize) -> bool>>,
+    #[allow(dead_code)] in_order: Option<bool>,
+    /// Whether the behaviour survives `MockState::reset_expected_behaviours_preserving_cumulative()`.
+    cumulative: bool,
+    /// A user-defined name of the behaviour.
+    label: Option<String>,
+    /// The constraint on the arguments passed to `matched_with_args()`.
+    args_constraint: Option<Box<dyn constraints::ArgsConstraint>>,
+    /// The violations of the `args_constraint` detected so far.
+    args_violations: RefCell<Vec<ArgsViolation>>,
+    /// The number of matches of each caller context, keyed by the context's hash, see `matched_with_context()`.
+    context_counts: RefCell<HashMap<u64, usize>>,
+    /// The ordinal of the only call of the method the behaviour applies to, any call if `None`.
+    nth_call: Option<usize>,
+    /// The trait/method keys whose calls all count as matches, see `spanning()`.
+    spanned_methods: Vec<(&'static str, &'static str)>,
+    /// Whether the behaviour is considered by the verification, see `set_enabled()`.
+    enabled: std::cell::Cell<bool>,
+    /// The phase during which the behaviour is active, any phase if `None`.
+    phase: Option<u32>,
+    /// How often the behaviour has been matched before its phase was active.
+    premature_matches: std::cell::Cell<usize>,
+    /// Whether only matches after `MockState::begin_act()` are counted.
+    post_act_scope: bool,
+    /// Whether an unsatisfied behaviour merely produces a warning, see `with_soft()`.
+    soft: bool,
+    /// Invoked once the behaviour becomes saturated for the first time, see `MockState::set_on_satisfied()`.
+    on_satisfied: RefCell<Option<Box<dyn FnMut()>>>,
+    /// Whether the behaviour is removed from its mock once it is satisfied, see `with_remove_on_satisfy()`.
+    remove_on_satisfy: bool,
+    /// Whether the behaviour has been satisfied and awaits its removal from the mock.
+    consumed: std::cell::Cell<bool>,
+    /// Behaviours with a higher priority are verified and reported first.
+    verify_priority: i32,
+    /// Compares the behaviour's bound variables with the ones of another behaviour.
+    bound_eq: Option<BoundEq>,
+    /// Renders the behaviour's bound variables for diagnostic messages.
+    bound_debug: Option<BoundDebug>,
+    /// The constraints on the order of the behaviour's matches relative to other behaviours.
+    ordering: Vec<OrderingConstraint>,
+    /// The sequence number of the behaviour's first match in the mock's invocation timeline.
+    first_sequence_number: std::cell::Cell<Option<usize>>,
+    /// The sequence number of the behaviour's most recent match in the mock's invocation timeline.
+    last_sequence_number: std::cell::Cell<Option<usize>>,
+    /// The sequence number of the match which first satisfied the behaviour.
+    saturated_sequence_number: std::cell::Cell<Option<usize>>,
+    /// The mock the behaviour is registered with.
+    registration: Option<Registration>,
+    /// When the behaviour has been matched for the first time.
+    #[cfg(feature = "timing")]
+    first_match: std::cell::Cell<Option<std::time::Instant>>,
+    /// When the behaviour has been matched for the last time.
+    #[cfg(feature = "timing")]
+    last_match: std::cell::Cell<Option<std::time::Instant>>,
+    /// The bound variables available to the behaviour's `ArgMatcher`.
+    pub bound: std::rc::Rc<dyn std::any::Any>,
+    /// A string representation of the behaviour's definition.
+    stmt_repr: String,
+    /// A human-readable description overriding `stmt_repr` in diagnostic messages.
+    description: Option<String>,
+}
+
+
+impl ExpectBehaviour {
+    /// Returns a builder for configuring a new behaviour step by step.
+    pub fn builder() -> ExpectBehaviourBuilder {
+        ExpectBehaviourBuilder::new()
+    }
+
+    /// Creates a new behaviour with the given expected minimum and maximum number of matches.
+    fn with_bounds(expected_min_matches: Option<usize>,
+                   expected_max_matches: Option<usize>,
+                   stmt_id: usize,
+                   bound: std::rc::Rc<dyn std::any::Any>,
+                   stmt_repr: &str)
+                   -> Self {
+        Self {
+            stmt_id,
+            num_matches: std::cell::Cell::new(0),
+            expected_min_matches: std::cell::Cell::new(expected_min_matches),
+            expected_max_matches: std::cell::Cell::new(expected_max_matches),
+            count_predicate: None,
+            in_order: None,
+            cumulative: false,
+            label: None,
+            args_constraint: None,
+            args_violations: RefCell::new(Vec::new()),
+            context_counts: RefCell::new(HashMap::new()),
+            nth_call: None,
+            spanned_methods: Vec::new(),
+            enabled: std::cell::Cell::new(true),
+            phase: None,
+            premature_matches: std::cell::Cell::new(0),
+            post_act_scope: false,
+            soft: false,
+            on_satisfied: RefCell::new(None),
+            remove_on_satisfy: false,
+            consumed: std::cell::Cell::new(false),
+            verify_priority: 0,
+            bound_eq: None,
+            bound_debug: None,
+            ordering: Vec::new(),
+            first_sequence_number: std::cell::Cell::new(None),
+            last_sequence_number: std::cell::Cell::new(None),
+            saturated_sequence_number: std::cell::Cell::new(None),
+            registration: None,
+            #[cfg(feature = "timing")]
+            first_match: std::cell::Cell::new(None),
+            #[cfg(feature = "timing")]
+            last_match: std::cell::Cell::new(None),
+            bound,
+            stmt_repr: stmt_repr.to_string(),
+            description: None,
+        }
+    }
+
+    /// Creates the behaviour configured by `builder`.
+    ///
+    /// # Panics
+    /// iff the builder's count range is invalid, see `ExpectBehaviourBuilder::build()`.
+    fn configured(builder: ExpectBehaviourBuilder) -> Self {
+        match builder.build() {
+            Ok(behaviour) => behaviour,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates a new behaviour which is satisfied if matched `times`.
+    pub fn with_times(times: usize,
+                      stmt_id: usize,
+                      bound: std::rc::Rc<dyn std::any::Any>,
+                      stmt_repr: &str)
+                      -> Self {
+        Self::configured(times_builder(stmt_id, bound, stmt_repr).times(times))
+    }
+
+    /// Creates a new behaviour which is satisfied if matched `at_least_times`.
+    pub fn with_at_least(at_least_times: usize,
+                         stmt_id: usize,
+                         bound: std::rc::Rc<dyn std::any::Any>,
+                         stmt_repr: &str)
+                         -> Self {
+        Self::configured(times_builder(stmt_id, bound, stmt_repr).at_least(at_least_times))
+    }
+
+    /// Creates a new behaviour which is satisfied if matched `at_most_times`.
+    pub fn with_at_most(at_most_times: usize,
+                        stmt_id: usize,
+                        bound: std::rc::Rc<dyn std::any::Any>,
+                        stmt_repr: &str)
+                        -> Self {
+        Self::configured(times_builder(stmt_id, bound, stmt_repr).at_most(at_most_times))
+    }
+
+    /// Creates a new behaviour which is satisfied if matched between `[at_least_times, at_most_times]` (inclusive endpoints).
+    ///
+    /// # Panics
+    /// iff `at_least_times > at_most_times` as the behaviour could never be satisfied.
+    pub fn with_between(at_least_times: usize,
+                        at_most_times: usize,
+                        stmt_id: usize,
+                        bound: std::rc::Rc<dyn std::any::Any>,
+                        stmt_repr: &str)
+                        -> Self {
+        Self::configured(times_builder(stmt_id, bound, stmt_repr).at_least(at_least_times).at_most(at_most_times))
+    }
+
+    /// Creates a new behaviour which is satisfied if matched between `[at_least_times, at_most_times]` (inclusive endpoints).
+    ///
+    /// Returns an error iff `at_least_times > at_most_times` as the behaviour could never be satisfied.
+    pub fn try_with_between(at_least_times: usize,
+                            at_most_times: usize,
+                            stmt_id: usize,
+                            bound: std::rc::Rc<dyn std::any::Any>,
+                            stmt_repr: &str)
+                            -> Result<Self, InvalidCountRange> {
+        times_builder(stmt_id, bound, stmt_repr).at_least(at_least_times).at_most(at_most_times).build()
+    }
+
+    /// Marks the behaviour as *cumulative* if `flag` is `true`.
+    ///
+    /// A cumulative behaviour keeps its match count when the mock's expected behaviours are reset
+    /// by `MockState::reset_expected_behaviours_preserving_cumulative()`.
+    /// Its saturation is always checked against the accumulated count, i.e., an expectation with an upper bound
+    /// might be satisfied after some iterations but fail after later ones.
+    /// A plain `reset_expected_behaviours()` removes cumulative behaviours as well.
+    pub fn with_cumulative(mut self, flag: bool) -> Self {
+        self.cumulative = flag;
+        self
+    }
+
+    /// Returns `true` iff the behaviour's match count survives resets preserving cumulative behaviours.
+    pub fn is_cumulative(&self) -> bool {
+        self.cumulative
+    }
+
+    /// Creates a new behaviour which only applies to the `n`th call of the method, starting at `1`.
+    ///
+    /// The behaviour is satisfied if the `n`th call occurred and matched, i.e., it is unsatisfied if the method is called fewer than `n` times.
+    /// Matches of other calls are ignored, see `is_applicable()`.
+    /// The calls are counted by `MockState::record_call()`.
+    pub fn with_nth_match(n: usize,
+                          stmt_id: usize,
+                          bound: std::rc::Rc<dyn std::any::Any>,
+                          stmt_repr: &str)
+                          -> Self {
+        let mut behaviour = Self::with_bounds(Some(1), Some(1), stmt_id, bound, stmt_repr);
+        behaviour.nth_call = Some(n);
+        behaviour
+    }
+
+    /// Returns `true` iff the behaviour applies to the current call of the method it is registered for.
+    ///
+    /// Only behaviours created by `with_nth_match()` are restricted to a single call.
+    /// A restricted behaviour which is not registered with a mock never applies.
+    pub fn is_applicable(&self) -> bool {
+        match self.nth_call {
+            None => true,
+            Some(n) => self.registration.as_ref().is_some_and(|registration| registration.call_count() == n),
+        }
+    }
+
+    /// Creates a new behaviour which is satisfied if the methods in `keys` are called `times` in total.
+    ///
+    /// Unlike an `ExpectGroup`, which combines the saturation of individual behaviours,
+    /// the calls of all listed methods are summed up by a single behaviour.
+    /// The behaviour must be registered by `MockState::add_spanning_expect_behaviour()`,
+    /// it is matched by `MockState::record_call()` for any call of the listed methods regardless of the arguments.
+    pub fn spanning(keys: Vec<(&'static str, &'static str)>,
+                    times: usize,
+                    stmt_id: usize,
+                    stmt_repr: &str)
+                    -> Self {
+        let mut behaviour = Self::with_times(times, stmt_id, std::rc::Rc::new(()), stmt_repr);
+        behaviour.spanned_methods = keys;
+        behaviour
+    }
+
+    /// Returns the trait/method keys the behaviour spans, empty if it is registered for a single method.
+    pub fn spanned_methods(&self) -> &[(&'static str, &'static str)] {
+        &self.spanned_methods
+    }
+
+    /// Creates a new behaviour which is satisfied if its number of matches satisfies `pred`.
+    ///
+    /// This allows for conditions which can't be expressed by a range, e.g., an even number of matches.
+    /// As the expected count can't be rendered, the behaviour's expected repetitions are described as custom.
+    pub fn with_count_predicate(pred: std::rc::Rc<dyn Fn(usize) -> bool>,
+                                stmt_id: usize,
+                                bound: std::rc::Rc<dyn std::any::Any>,
+                                stmt_repr: &str)
+                                -> Self {
+        let mut behaviour = Self::with_bounds(None, None, stmt_id, bound, stmt_repr);
+        behaviour.count_predicate = Some(pred);
+        behaviour
+    }
+
+    /// Creates a new behaviour which is satisfied if matched `times` with arguments related by `cmp`.
+    ///
+    /// Each call's arguments are compared to the arguments of the previous call, `cmp(previous, current)` must hold.
+    /// E.g., `|prev: &u32, next: &u32| prev <= next` requires non-decreasing arguments.
+    /// The arguments are passed to the behaviour by `matched_with_args()`.
+    pub fn with_monotonic_args<T, F>(cmp: F,
+                                     times: usize,
+                                     stmt_id: usize,
+                                     bound: std::rc::Rc<dyn std::any::Any>,
+                                     stmt_repr: &str)
+                                     -> Self
+        where T: Clone + 'static,
+              F: Fn(&T, &T) -> bool + 'static
+    {
+        let mut behaviour = Self::with_times(times, stmt_id, bound, stmt_repr);
+        behaviour.args_constraint = Some(Box::new(constraints::MonotonicArgs::new(std::rc::Rc::new(cmp))));
+        behaviour
+    }
+
+    /// Creates a new behaviour which is satisfied if matched `times` with arguments which change on every match.
+    ///
+    /// Each call's arguments are compared to the arguments of the previous call, `eq(previous, current)` must not hold.
+    /// The first match always passes, e.g., `|prev: &u32, next: &u32| prev == next` rejects repeated values.
+    /// The arguments are passed to the behaviour by `matched_with_args()`.
+    pub fn with_changing_args<T, F>(eq: F,
+                                    times: usize,
+                                    stmt_id: usize,
+                                    bound: std::rc::Rc<dyn std::any::Any>,
+                                    stmt_repr: &str)
+                                    -> Self
+        where T: Clone + 'static,
+              F: Fn(&T, &T) -> bool + 'static
+    {
+        let mut behaviour = Self::with_times(times, stmt_id, bound, stmt_repr);
+        behaviour.args_constraint = Some(Box::new(constraints::ChangingArgs::new(std::rc::Rc::new(eq))));
+        behaviour
+    }
+
+    /// Creates a new behaviour which is satisfied once it has been matched with each of the `required` arguments.
+    ///
+    /// The order of the matches does not matter and arguments outside of the set are accepted.
+    /// Arguments are compared by `eq`, the verification lists the required arguments which have never been observed.
+    /// The arguments are passed to the behaviour by `matched_with_args()`.
+    pub fn with_required_args_set<T, F>(required: Vec<T>,
+                                        eq: F,
+                                        stmt_id: usize,
+                                        bound: std::rc::Rc<dyn std::any::Any>,
+                                        stmt_repr: &str)
+                                        -> Self
+        where T: std::fmt::Debug + 'static,
+              F: Fn(&T, &T) -> bool + 'static
+    {
+        let mut behaviour = Self::with_bounds(None, None, stmt_id, bound, stmt_repr);
+        behaviour.args_constraint = Some(Box::new(constraints::RequiredArgsSet::new(std::rc::Rc::new(required),
+                                                                                  std::rc::Rc::new(eq))));
+        behaviour
+    }
+
+    /// Creates a new behaviour which is satisfied once it has been matched with at least `n` distinct arguments.
+    ///
+    /// Arguments are distinguished by the key returned by `key_fn`, repeated keys count once. `n == 0` is trivially satisfied.
+    /// Only the hashes of the keys are stored, keys with colliding hashes are counted as the same key.
+    /// The arguments are passed to the behaviour by `matched_with_args()`.
+    pub fn with_at_least_distinct<T, K, F>(n: usize,
+                                           key_fn: F,
+                                           stmt_id: usize,
+                                           bound: std::rc::Rc<dyn std::any::Any>,
+                                           stmt_repr: &str)
+                                           -> Self
+        where T: 'static,
+              K: std::hash::Hash,
+              F: Fn(&T) -> K + 'static
+    {
+        let mut behaviour = Self::with_bounds(None, None, stmt_id, bound, stmt_repr);
+        behaviour.args_constraint = Some(Box::new(constraints::DistinctArgs::new(n, constraints::key_hasher(key_fn))));
+        behaviour
+    }
+
+    /// Creates a new behaviour which is satisfied as long as no two of its matches have arguments with the same key.
+    ///
+    /// Each repeated key is recorded as a violation at the index of the repeating match,
+    /// the verification reports the violations in the order they have been observed.
+    /// The behaviour has no expected count, one can be set by `set_expected_min()` and `set_expected_max()`.
+    /// The arguments are passed to the behaviour by `matched_with_args()`.
+    pub fn with_all_distinct_args<T, K, F>(key_fn: F,
+                                           stmt_id: usize,
+                                           bound: std::rc::Rc<dyn std::any::Any>,
+                                           stmt_repr: &str)
+                                           -> Self
+        where T: 'static,
+              K: std::hash::Hash,
+              F: Fn(&T) -> K + 'static
+    {
+        let mut behaviour = Self::with_bounds(None, None, stmt_id, bound, stmt_repr);
+        behaviour.args_constraint = Some(Box::new(constraints::AllDistinctArgs::new(constraints::key_hasher(key_fn))));
+        behaviour
+    }
+
+    /// Adds the expected bounds of the `other` behaviour to the bounds of this behaviour.
+    ///
+    /// See `MockState::add_expect_behaviour_deduplicated()`.
+    fn merge_bounds(&mut self, other: &ExpectBehaviour) {
+        if self.count_predicate.is_some() {
+            return;
+        }
+        let (min, other_min) = (self.expected_min_matches.get(), other.expected_min_matches.get());
+        if min.is_some() || other_min.is_some() {
+            self.expected_min_matches.set(Some(min.unwrap_or(0) + other_min.unwrap_or(0)));
+        }
+        self.expected_max_matches.set(match (self.expected_max_matches.get(), other.expected_max_matches.get()) {
+            (Some(max), Some(other_max)) => Some(max + other_max),
+            _ => None,
+        });
+    }
+
+    /// Replaces the expected minimum number of matches, `None` removes the minimum.
+    ///
+    /// The behaviour's saturation is evaluated against the new bound from now on, including the matches which already occurred.
+    /// The bound is ignored by behaviours with a custom count predicate.
+    ///
+    /// # Panics
+    /// iff the new minimum exceeds the behaviour's maximum, see `try_set_expected_bounds()` to replace both at once.
+    pub fn set_expected_min(&self, min: Option<usize>) {
+        if let Err(err) = self.try_set_expected_bounds(min, self.expected_max_matches.get()) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Replaces the expected maximum number of matches, `None` removes the maximum.
+    ///
+    /// See `set_expected_min()`.
+    ///
+    /// # Panics
+    /// iff the new maximum is below the behaviour's minimum.
+    pub fn set_expected_max(&self, max: Option<usize>) {
+        if let Err(err) = self.try_set_expected_bounds(self.expected_min_matches.get(), max) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Replaces the expected minimum and maximum number of matches, `None` removes the respective bound.
+    ///
+    /// Returns an error and keeps the current bounds iff `min > max` as the behaviour could never be satisfied.
+    pub fn try_set_expected_bounds(&self, min: Option<usize>, max: Option<usize>) -> Result<(), InvalidCountRange> {
+        if let (Some(at_least_times), Some(at_most_times)) = (min, max) {
+            if at_least_times > at_most_times {
+                return Err(InvalidCountRange {
+                    at_least_times,
+                    at_most_times,
+                    stmt_repr: self.stmt_repr.clone(),
+                });
+            }
+        }
+        self.expected_min_matches.set(min);
+        self.expected_max_matches.set(max);
+        Ok(())
+    }
+
+    /// Sets how the behaviour's bound variables are rendered in verification reports, e.g., with their `Debug` representation.
+    pub fn with_bound_debug(mut self, bound_debug: BoundDebug) -> Self {
+        self.bound_debug = Some(bound_debug);
+        self
+    }
+
+    /// Returns the description of the behaviour followed by its rendered bound variables, if available.
+    ///
+    /// See `with_bound_debug()`.
+    pub fn describe_with_bound(&self) -> String {
+        match self.bound_debug {
+            Some(ref bound_debug) => format!("{} with bound {}", self.describe(), bound_debug()),
+            None => self.describe().to_string(),
+        }
+    }
+
+    /// Sets how the behaviour's bound variables are compared with the ones of another behaviour.
+    ///
+    /// The comparison decides whether `MockState::add_expect_behaviour_deduplicated()` treats behaviours as duplicates.
+    /// Without a comparator bound variables are only equal if they are the same object, see `Rc::ptr_eq()`.
+    pub fn with_bound_eq(mut self, bound_eq: BoundEq) -> Self {
+        self.bound_eq = Some(bound_eq);
+        self
+    }
+
+    /// Returns `true` iff the bound variables of the behaviours are equal, see `with_bound_eq()`.
+    pub fn has_equal_bound(&self, other: &ExpectBehaviour) -> bool {
+        bounds_equal(&self.bound_eq, &self.bound, &other.bound)
+    }
+
+    /// Returns the user-defined name of the behaviour, if any.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Adds a constraint on the order of the behaviour's matches relative to the behaviour with another label.
+    ///
+    /// The constraint is checked by the verification of the mock the behaviour is registered with.
+    pub fn with_ordering(mut self, constraint: OrderingConstraint) -> Self {
+        self.ordering.push(constraint);
+        self
+    }
+
+    /// Returns the constraints on the order of the behaviour's matches.
+    pub fn ordering(&self) -> &[OrderingConstraint] {
+        &self.ordering
+    }
+
+    /// Returns `true` iff the behaviour's matches violate the `constraint` with respect to the `other` behaviour.
+    ///
+    /// A constraint is only violated if both behaviours have been matched.
+    fn violates_ordering(&self, constraint: &OrderingConstraint, other: &ExpectBehaviour) -> bool {
+        match *constraint {
+            OrderingConstraint::Before(_) => match (self.last_sequence_number.get(), other.first_sequence_number.get()) {
+                (Some(last), Some(other_first)) => last > other_first,
+                _ => false,
+            },
+            OrderingConstraint::After(_) => match (self.first_sequence_number.get(), other.last_sequence_number.get()) {
+                (Some(first), Some(other_last)) => first < other_last,
+                _ => false,
+            },
+        }
+    }
+
+    /// Returns a copy of the behaviour's definition which has not been matched yet.
+    fn unmatched_copy(&self) -> Self {
+        Self {
+            stmt_id: self.stmt_id,
+            num_matches: std::cell::Cell::new(0),
+            expected_min_matches: std::cell::Cell::new(self.expected_min_matches.get()),
+            expected_max_matches: std::cell::Cell::new(self.expected_max_matches.get()),
+            count_predicate: self.count_predicate.clone(),
+            in_order: self.in_order,
+            cumulative: self.cumulative,
+            label: self.label.clone(),
+            args_constraint: self.args_constraint.as_ref().map(|constraint| constraint.fresh()),
+            args_violations: RefCell::new(Vec::new()),
+            context_counts: RefCell::new(HashMap::new()),
+            nth_call: self.nth_call,
+            spanned_methods: self.spanned_methods.clone(),
+            enabled: std::cell::Cell::new(self.enabled.get()),
+            phase: self.phase,
+            premature_matches: std::cell::Cell::new(0),
+            post_act_scope: self.post_act_scope,
+            soft: self.soft,
+            on_satisfied: RefCell::new(None),
+            remove_on_satisfy: self.remove_on_satisfy,
+            consumed: std::cell::Cell::new(false),
+            verify_priority: self.verify_priority,
+            bound_eq: self.bound_eq.clone(),
+            bound_debug: self.bound_debug.clone(),
+            ordering: self.ordering.clone(),
+            first_sequence_number: std::cell::Cell::new(None),
+            last_sequence_number: std::cell::Cell::new(None),
+            saturated_sequence_number: std::cell::Cell::new(None),
+            registration: None,
+            #[cfg(feature = "timing")]
+            first_match: std::cell::Cell::new(None),
+            #[cfg(feature = "timing")]
+            last_match: std::cell::Cell::new(None),
+            bound: self.bound.clone(),
+            stmt_repr: self.stmt_repr.clone(),
+            description: self.description.clone(),
+        }
+    }
+
+    /// Returns an owned summary of the behaviour registered for the trait's method.
+    fn summary(&self, requested_trait: &'static str, method: &'static str) -> BehaviourSummary {
+        BehaviourSummary {
+            requested_trait,
+            method,
+            stmt_id: self.stmt_id,
+            num_matches: self.num_matches.get(),
+            expected_min_matches: self.expected_min_matches.get(),
+            expected_max_matches: self.expected_max_matches.get(),
+            is_saturated: self.is_saturated(),
+            description: self.describe().to_string(),
+        }
+    }
+
+    /// Enables or disables the behaviour without removing it from its mock.
+    ///
+    /// A disabled behaviour is ignored by the verification and ignores its matches.
+    /// Behaviours are enabled by default.
+    pub fn set_enabled(&self, flag: bool) {
+        self.enabled.set(flag);
+    }
+
+    /// Returns `true` iff the behaviour is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Assigns the behaviour to a phase of the mock, see `MockState::advance_phase()`.
+    pub fn with_phase(mut self, phase: u32) -> Self {
+        self.phase = Some(phase);
+        self
+    }
+
+    /// Passing `true` turns the behaviour into a *soft* expectation which warns but never fails the verification.
+    ///
+    /// An unsatisfied soft behaviour is reported as a warning, i.e., it is sent to the message sink by `verify()`
+    /// without failing or panicking and it is not part of a `VerificationError`. The same applies to its other failures,
+    /// e.g., of phases, ordering constraints, and in-order consumption, and to groups whose members are all soft.
+    /// This allows to observe a check before promoting it to a hard one. Behaviours are hard by default.
+    pub fn with_soft(mut self, flag: bool) -> Self {
+        self.soft = flag;
+        self
+    }
+
+    /// Returns `true` iff the behaviour is a soft expectation, see `with_soft()`.
+    pub fn is_soft(&self) -> bool {
+        self.soft
+    }
+
+    /// Passing `true` ignores the matches of the behaviour until `MockState::begin_act()` has been called.
+    ///
+    /// This separates incidental calls while arranging a test from the calls of the act phase which are verified.
+    /// A behaviour which is not registered with a mock is never scoped.
+    pub fn with_post_act_scope(mut self, flag: bool) -> Self {
+        self.post_act_scope = flag;
+        self
+    }
+
+    /// Returns `true` iff the behaviour is scoped to the act phase and the act phase of its mock has not begun.
+    fn is_before_act(&self) -> bool {
+        self.post_act_scope &&
+        self.registration.as_ref().is_some_and(|registration| registration.shared.act_position.get().is_none())
+    }
+
+    /// Passing `true` removes the behaviour from its mock the moment it becomes satisfied.
+    ///
+    /// Later verifications no longer require the behaviour,
+    /// which suits interactive sessions where expectations are consumed as they are met.
+    /// For a count range the removal happens once the behaviour enters the range, e.g., after the second match of
+    /// `with_between(2, 4, ..)`; later calls are not matched by the behaviour anymore.
+    /// A behaviour with a minimum of `0`, e.g., `with_at_most(..)`, is satisfied from the start and never enters
+    /// its range by a match, therefore it is not removed and its maximum is still verified.
+    pub fn with_remove_on_satisfy(mut self, flag: bool) -> Self {
+        self.remove_on_satisfy = flag;
+        self
+    }
+
+    /// Sets the priority of the behaviour's verification, behaviours with a higher priority are reported first.
+    ///
+    /// The priority takes precedence over the mock's `ReportOrdering` but doesn't affect matching.
+    /// Behaviours have the priority `0` by default.
+    pub fn with_verify_priority(mut self, priority: i32) -> Self {
+        self.verify_priority = priority;
+        self
+    }
+
+    /// Returns the priority of the behaviour's verification.
+    pub fn verify_priority(&self) -> i32 {
+        self.verify_priority
+    }
+
+    /// Returns the phase the behaviour is assigned to, if any.
+    pub fn phase(&self) -> Option<u32> {
+        self.phase
+    }
+
+    /// Returns `true` iff the behaviour's phase has not been reached by the mock it is registered with.
+    fn is_premature(&self) -> bool {
+        match (self.phase, self.registration.as_ref()) {
+            (Some(phase), Some(registration)) => phase > registration.shared.current_phase.get(),
+            _ => false,
+        }
+    }
+
+    /// Returns how often the behaviour has been matched before its phase was active.
+    pub fn premature_matches(&self) -> usize {
+        self.premature_matches.get()
+    }
+
+    /// Returns `true` iff a match is ignored as the mock doesn't record matches, the behaviour is disabled,
+    /// it doesn't apply to the current call, or the act phase has not begun.
+    fn ignores_match(&self) -> bool {
+        cfg!(feature = "disabled") || !is_recording(&self.registration) || !self.is_enabled() || !self.is_applicable() ||
+        self.is_before_act()
+    }
+
+    /// Returns `true` iff a match is counted by the behaviour, i.e., it is neither ignored nor premature.
+    fn counts_match(&self) -> bool {
+        !self.ignores_match() && !self.is_premature()
+    }
+
+    /// Notifies the behaviour that it has been matched.
+    ///
+    /// Does nothing if the mock the behaviour is registered with does not record matches, if the behaviour is disabled,
+    /// or if it does not apply to the current call.
+    /// A match before the behaviour's phase is active is counted as premature instead.
+    /// The callback set by `MockState::set_on_satisfied()` is invoked if the match saturates the behaviour for the first time.
+    pub fn matched(&self) {
+        if !self.counts_match() {
+            if !self.ignores_match() {
+                self.premature_matches.set(self.premature_matches.get() + 1);
+            }
+            return;
+        }
+        let was_saturated = self.is_saturated();
+        self.num_matches.set(self.num_matches.get() + 1);
+        let became_saturated = !was_saturated && self.is_saturated();
+        if became_saturated {
+            let callback = self.on_satisfied.borrow_mut().take();
+            if let Some(mut callback) = callback {
+                callback();
+            }
+            if self.remove_on_satisfy {
+                self.consumed.set(true);
+                self.enabled.set(false);
+            }
+        }
+        if let Some(ref registration) = self.registration {
+            let sequence_number = registration.record_match(self.stmt_id, BehaviourKind::Expect);
+            if self.first_sequence_number.get().is_none() {
+                self.first_sequence_number.set(Some(sequence_number));
+            }
+            self.last_sequence_number.set(Some(sequence_number));
+            if became_saturated && self.saturated_sequence_number.get().is_none() {
+                self.saturated_sequence_number.set(Some(sequence_number));
+            }
+        }
+        #[cfg(feature = "timing")]
+        {
+            let now = self.registration.as_ref().map_or_else(std::time::Instant::now, |r| r.shared.now());
+            if self.first_match.get().is_none() {
+                self.first_match.set(Some(now));
+            }
+            self.last_match.set(Some(now));
+        }
+    }
+
+    /// Returns `true` iff the behaviour has been matched at least once.
+    pub fn has_matched(&self) -> bool {
+        self.num_matches.get() > 0
+    }
+
+    /// Returns when the behaviour has been matched for the first time, `None` if it has never been matched.
+    #[cfg(feature = "timing")]
+    pub fn first_match(&self) -> Option<std::time::Instant> {
+        self.first_match.get()
+    }
+
+    /// Returns when the behaviour has been matched for the last time, `None` if it has never been matched.
+    #[cfg(feature = "timing")]
+    pub fn last_match(&self) -> Option<std::time::Instant> {
+        self.last_match.get()
+    }
+
+    /// Returns `true` iff the behaviour has been matched less often than its expected minimum.
+    ///
+    /// A behaviour with a custom count predicate has no minimum.
+    pub fn is_under_saturated(&self) -> bool {
+        self.num_matches.get() < self.expected_min_matches.get().unwrap_or(0)
+    }
+
+    /// Returns `true` iff the behaviour has been matched more often than its expected maximum.
+    ///
+    /// A behaviour with a custom count predicate has no maximum.
+    pub fn is_over_saturated(&self) -> bool {
+        self.num_matches.get() > self.expected_max_matches.get().unwrap_or(usize::MAX)
+    }
+
+    /// Returns by how many matches the behaviour falls short of its minimum or exceeds its maximum.
+    ///
+    /// A behaviour with a custom count predicate has no distance.
+    pub fn distance_from_target(&self) -> usize {
+        if self.count_predicate.is_some() {
+            return 0;
+        }
+        let num_matches = self.num_matches.get();
+        let min = self.expected_min_matches.get().unwrap_or(0);
+        let max = self.expected_max_matches.get().unwrap_or(usize::MAX);
+        if num_matches < min {
+            min - num_matches
+        } else {
+            num_matches.saturating_sub(max)
+        }
+    }
+
+    /// Returns a description of the behaviour's expected repetitions, e.g., `exactly 2` or `at least 1`.
+    pub fn expected_repetitions(&self) -> String {
+        if self.count_predicate.is_some() {
+            return "a custom number of times".to_string();
+        }
+        match (self.expected_min_matches.get(), self.expected_max_matches.get()) {
+            (Some(min), Some(max)) if min == max => format!("exactly {}", min),
+            (Some(min), Some(max)) => format!("between {} and {}", min, max),
+            (Some(min), None) => format!("at least {}", min),
+            (None, Some(max)) => format!("at most {}", max),
+            (None, None) => "any number of times".to_string(),
+        }
+    }
+
+    /// Notifies the behaviour that it has been matched with `args`.
+    ///
+    /// Besides counting the match, the arguments are checked against the behaviour's argument constraint, if any.
+    /// A violated constraint is recorded together with the index of the offending match.
+    pub fn matched_with_args<T: std::any::Any>(&self, args: T) {
+        if !self.counts_match() {
+            self.matched();
+            return;
+        }
+        let call_index = self.num_matches.get();
+        self.matched();
+        if let Some(ref constraint) = self.args_constraint {
+            if let Err(message) = constraint.check(call_index, &args) {
+                self.args_violations.borrow_mut().push(ArgsViolation { call_index, message });
+            }
+        }
+    }
+
+    /// Notifies the behaviour that it has been matched on behalf of the caller `context`.
+    ///
+    /// Each context may match the behaviour at most once, a repeated match of a context is recorded as a violation
+    /// at the index of the repeating match and fails the verification. This models per-session idempotency.
+    /// The dispatch code passes a value identifying the caller, e.g., a session token the test attached by
+    /// `MockState::set_user_data()`. Contexts are told apart by their hash, i.e., the key must implement `Hash`
+    /// consistently with its equality; keys with colliding hashes are treated as the same context.
+    pub fn matched_with_context<K: std::hash::Hash>(&self, context: &K) {
+        if !self.counts_match() {
+            self.matched();
+            return;
+        }
+        let call_index = self.num_matches.get();
+        self.matched();
+        let key = constraints::key_hasher(|context: &&K| *context)(&context);
+        let mut context_counts = self.context_counts.borrow_mut();
+        let count = context_counts.entry(key).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            let message = format!("caller context matched {} times", count);
+            self.args_violations.borrow_mut().push(ArgsViolation { call_index, message });
+        }
+    }
+
+    /// Returns the violations of the behaviour's argument constraint detected so far.
+    pub fn args_violations(&self) -> Vec<ArgsViolation> {
+        self.args_violations.borrow().clone()
+    }
+
+    /// Returns `true` iff current number of matches would satify the behaviours expected repetitions.
+    ///
+    /// A behaviour whose argument constraint has been violated or whose argument requirements are unmet is not saturated.
+    pub fn is_saturated(&self) -> bool {
+        let count_satisfied = match self.count_predicate {
+            Some(ref pred) => pred(self.num_matches.get()),
+            None => {
+                self.expected_min_matches.get().unwrap_or(0) <= self.num_matches.get() &&
+                self.num_matches.get() <= self.expected_max_matches.get().unwrap_or(usize::MAX)
+            }
+        };
+        count_satisfied && self.args_violations.borrow().is_empty() && self.unmet_args_requirements().is_none()
+    }
+
+    /// Returns a description of the argument requirements the behaviour's matches have not met yet, if any.
+    pub fn unmet_args_requirements(&self) -> Option<String> {
+        self.args_constraint.as_ref().and_then(|constraint| constraint.unmet_requirements())
+    }
+
+    /// Returns a description of the behaviour.
+    ///
+    /// The description set by `with_description()` takes precedence over the behaviour's `stmt_repr`.
+    pub fn describe(&self) -> &str {
+        self.description.as_ref().unwrap_or(&self.stmt_repr)
+    }
+
+    /// Sets a human-readable description of the behaviour's intent which replaces its `stmt_repr` in diagnostic messages.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Returns the behaviour's bound variables as the concrete type `T`.
+    ///
+    /// Panics if the bound variables are not of type `T`, see `try_bound_as()`.
+    pub fn bound_as<T: 'static>(&self) -> &T {
+        downcast_bound(&self.bound, &self.stmt_repr)
+    }
+
+    /// Returns the behaviour's bound variables as the concrete type `T`.
+    ///
+    /// Fails with the expected and the actual `TypeId` if the bound variables are not of type `T`.
+    pub fn try_bound_as<T: 'static>(&self) -> Result<&T, BoundTypeMismatch> {
+        try_downcast_bound(&self.bound, &self.stmt_repr)
+    }
+}
+
+impl std::fmt::Display for ExpectBehaviour {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[#{}] {} (matched {}x)", self.stmt_id, self.describe(), self.num_matches.get())
+    }
+}
+
+/// Configures and creates an `ExpectBehaviour`.
+///
+/// Unlike the `ExpectBehaviour::with_*` constructors the builder allows to set each option of the behaviour separately.
+/// Options which are not set default to an unbounded number of matches, the id `0`, a unit bound, and an empty representation.
+pub struct ExpectBehaviourBuilder {
+    stmt_id: usize,
+    bound: std::rc::Rc<dyn std::any::Any>,
+    stmt_repr: String,
+    expected_min_matches: Option<usize>,
+    expected_max_matches: Option<usize>,
+    in_order: Option<bool>,
+    label: Option<String>,
+}
+
+impl ExpectBehaviourBuilder {
+    /// Creates a new builder with all options set to their defaults.
+    pub fn new() -> Self {
+        Self {
+            stmt_id: 0,
+            bound: std::rc::Rc::new(()),
+            stmt_repr: String::new(),
+            expected_min_matches: None,
+            expected_max_matches: None,
+            in_order: None,
+            label: None,
+        }
+    }
+
+    /// Sets the unique id of the behaviour within the mocked method to which it belongs.
+    pub fn stmt_id(mut self, stmt_id: usize) -> Self {
+        self.stmt_id = stmt_id;
+        self
+    }
+
+    /// Sets the bound variables available to the behaviour's `ArgMatcher`.
+    pub fn bound(mut self, bound: std::rc::Rc<dyn std::any::Any>) -> Self {
+        self.bound = bound;
+        self
+    }
+
+    /// Sets the string representation of the behaviour's definition.
+    pub fn repr(mut self, stmt_repr: &str) -> Self {
+        self.stmt_repr = stmt_repr.to_string();
+        self
+    }
+
+    /// Sets the expected minimum number of matches.
+    pub fn at_least(mut self, at_least_times: usize) -> Self {
+        self.expected_min_matches = Some(at_least_times);
+        self
+    }
+
+    /// Sets the expected maximum number of matches.
+    pub fn at_most(mut self, at_most_times: usize) -> Self {
+        self.expected_max_matches = Some(at_most_times);
+        self
+    }
+
+    /// Sets the expected minimum and maximum number of matches to `times`.
+    pub fn times(self, times: usize) -> Self {
+        self.at_least(times).at_most(times)
+    }
+
+    /// Sets whether the behaviour's matches should occur in order.
+    pub fn in_order(mut self, flag: bool) -> Self {
+        self.in_order = Some(flag);
+        self
+    }
+
+    /// Sets a user-defined name for the behaviour.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Creates the configured behaviour.
+    ///
+    /// Returns an error iff the expected minimum number of matches exceeds the maximum.
+    pub fn build(self) -> Result<ExpectBehaviour, InvalidCountRange> {
+        if let (Some(at_least_times), Some(at_most_times)) = (self.expected_min_matches, self.expected_max_matches) {
+            if at_least_times > at_most_times {
+                return Err(InvalidCountRange {
+                    at_least_times,
+                    at_most_times,
+                    stmt_repr: self.stmt_repr,
+                });
+            }
+        }
+        let mut behaviour = ExpectBehaviour::with_bounds(self.expected_min_matches,
+                                                         self.expected_max_matches,
+                                                         self.stmt_id,
+                                                         self.bound,
+                                                         &self.stmt_repr);
+        behaviour.in_order = self.in_order;
+        behaviour.label = self.label;
+        Ok(behaviour)
+    }
+}
+
+impl Default for ExpectBehaviourBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a builder for the behaviour with the arguments shared by the `ExpectBehaviour::with_*` constructors.
+fn times_builder(stmt_id: usize, bound: std::rc::Rc<dyn std::any::Any>, stmt_repr: &str) -> ExpectBehaviourBuilder {
+    ExpectBehaviourBuilder::new().stmt_id(stmt_id).bound(bound).repr(stmt_repr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    #[should_panic]
+    fn with_between_rejects_inverted_range() {
+        ExpectBehaviour::with_between(5, 2, 0, Rc::new(()), "inverted");
+    }
+
+    #[test]
+    fn try_with_between_rejects_inverted_range() {
+        assert!(ExpectBehaviour::try_with_between(5, 2, 0, Rc::new(()), "inverted").is_err());
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    #[should_panic(expected = "There are unsatisfied expected behaviours for mocked traits.")]
+    fn default_verifies_on_drop() {
+        let mock = MockState::default();
+        assert!(mock.verify_on_drop);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+    }
+
+    #[test]
+    fn unverified_mock_does_not_panic_on_drop() {
+        let mock = MockState::new_unverified();
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+        drop(mock);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn always_given_behaviour_never_exhausts() {
+        let mock = MockState::new();
+        mock.add_given_behaviour("Trait", "method", GivenBehaviour::always(0, "always"));
+        for _ in 0..100 {
+            let behaviour = mock.select_given("Trait", "method", |_| true).expect("always behaviour selected");
+            assert_eq!(behaviour.stmt_id, 0);
+            behaviour.matched();
+            assert!(!behaviour.is_saturated());
+        }
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    #[should_panic(expected = "cannot register behaviours during verification")]
+    fn registering_during_verification_panics() {
+        let slot: Rc<RefCell<std::rc::Weak<MockState>>> = Rc::new(RefCell::new(std::rc::Weak::new()));
+        let mut mock = MockState::new();
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+        let callback_slot = slot.clone();
+        mock.set_on_verification_failure(Box::new(move |_| {
+            if let Some(mock) = callback_slot.borrow().upgrade() {
+                mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 1, Rc::new(()), "reentrant"));
+            }
+        }));
+        let mock = Rc::new(mock);
+        *slot.borrow_mut() = Rc::downgrade(&mock);
+        mock.verify();
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn uncounted_match_skips_args_constraint() {
+        let behaviour = ExpectBehaviour::with_all_distinct_args(|arg: &i32| *arg, 0, Rc::new(()), "distinct");
+        behaviour.matched_with_args(1);
+        behaviour.set_enabled(false);
+        behaviour.matched_with_args(1);
+        assert!(behaviour.args_violations().is_empty());
+        assert_eq!(behaviour.num_matches.get(), 1);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn uncounted_match_skips_context_check() {
+        let behaviour = ExpectBehaviour::with_bounds(None, None, 0, Rc::new(()), "per context");
+        behaviour.matched_with_context(&"session");
+        behaviour.set_enabled(false);
+        behaviour.matched_with_context(&"session");
+        assert!(behaviour.args_violations().is_empty());
+        behaviour.set_enabled(true);
+        behaviour.matched_with_context(&"session");
+        assert_eq!(behaviour.args_violations().len(), 1);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn try_verify_counts_as_explicit_verification() {
+        let mut mock = MockState::new();
+        mock.should_require_explicit_verify(true);
+        mock.should_verify_on_drop(false);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+        assert!(mock.try_verify().is_err());
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn alias_refers_to_behaviour_of_its_method_only() {
+        let mock = MockState::new();
+        mock.add_expect_behaviour("Trait", "first", ExpectBehaviour::with_times(1, 0, Rc::new(()), "first")
+                                      .with_ordering(OrderingConstraint::Before("second".to_string())));
+        mock.add_expect_behaviour("Trait", "second", ExpectBehaviour::with_times(1, 1, Rc::new(()), "second"));
+        mock.add_expect_behaviour("Trait", "other", ExpectBehaviour::with_times(1, 1, Rc::new(()), "other"));
+        mock.alias("second", ("Trait", "second", 1));
+        assert_eq!(mock.resolve_alias("second"), Some(("Trait", "second", 1)));
+        for method in &["other", "first", "second"] {
+            mock.expect_behaviours.borrow()[&("Trait", *method)][0].matched();
+        }
+        assert!(mock.try_verify().is_ok());
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn in_order_queue_skips_behaviour_saturated_from_the_start() {
+        let mut mock = MockState::new();
+        mock.should_consume_expectations_in_order("Trait", "method", true);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_bounds(None, Some(1), 0, Rc::new(()), "at most once"));
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 1, Rc::new(()), "once"));
+        mock.expect_behaviours.borrow()[&("Trait", "method")][1].matched();
+        assert!(mock.try_verify().is_ok());
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn soft_behaviour_failures_are_warnings() {
+        let mut mock = MockState::new();
+        mock.should_verify_on_drop(false);
+        mock.should_consume_expectations_in_order("Trait", "method", true);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "first").with_soft(true));
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 1, Rc::new(()), "second").with_soft(true));
+        mock.add_expect_behaviour("Trait", "grouped", ExpectBehaviour::with_times(1, 2, Rc::new(()), "grouped").with_soft(true));
+        mock.add_expect_group(ExpectGroup::any_of(vec![("Trait", "grouped", 2)]));
+        mock.expect_behaviours.borrow()[&("Trait", "method")][1].matched();
+        let report = mock.verification_report();
+        assert!(report.is_satisfied());
+        assert_eq!(report.warnings().len(), 3);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn count_relation_accepts_equal_counts_for_inclusive_relations() {
+        let mock = MockState::new();
+        mock.add_given_behaviour("Trait", "a", GivenBehaviour::always(0, "a"));
+        mock.add_given_behaviour("Trait", "b", GivenBehaviour::always(0, "b"));
+        for method in &["a", "b"] {
+            mock.select_given("Trait", method, |_| true).expect("always behaviour selected").matched();
+        }
+        assert!(assert_count_relation(&mock, ("Trait", "a"), CountRelation::GreaterOrEqual, ("Trait", "b")).is_ok());
+        assert!(assert_count_relation(&mock, ("Trait", "a"), CountRelation::LessOrEqual, ("Trait", "b")).is_ok());
+        assert!(assert_count_relation(&mock, ("Trait", "a"), CountRelation::Greater, ("Trait", "b")).is_err());
+    }
+
+    #[test]
+    fn read_only_queries_do_not_create_shared_state() {
+        let mock = MockState::new();
+        assert!(mock.is_recording());
+        assert_eq!(mock.current_phase(), 0);
+        assert!(mock.invocation_timeline().is_empty());
+        assert_eq!(mock.invocation_count("Trait", "method"), 0);
+        assert_eq!(mock.calls_in_last("Trait", "method", 10), 0);
+        assert!(!mock.are_records_truncated());
+        assert_eq!(mock.act_position(), None);
+        mock.save_counts();
+        assert!(mock.shared.get().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "can never be satisfied")]
+    fn set_expected_min_rejects_minimum_above_maximum() {
+        ExpectBehaviour::with_at_most(2, 0, Rc::new(()), "at most twice").set_expected_min(Some(3));
+    }
+
+    #[test]
+    fn try_set_expected_bounds_keeps_bounds_on_error() {
+        let behaviour = ExpectBehaviour::with_between(1, 2, 0, Rc::new(()), "between");
+        assert!(behaviour.try_set_expected_bounds(Some(5), Some(4)).is_err());
+        assert!(behaviour.try_set_expected_bounds(Some(5), Some(6)).is_ok());
+        assert_eq!(behaviour.expected_repetitions(), "between 5 and 6");
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn replay_dispatches_logged_calls_to_target() {
+        let mut source = MockState::new();
+        source.should_log_calls(true);
+        source.log_call("Trait", "method", "1".to_string());
+        source.log_call("Trait", "method", "2".to_string());
+        let target = MockState::new();
+        target.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "two"));
+        source.replay_into(&target, |target, entry| {
+            if entry.args_repr == "2" {
+                target.expect_behaviours.borrow()[&(entry.requested_trait, entry.method)][0].matched();
+            }
+        });
+        assert_eq!(target.invocation_count("Trait", "method"), 2);
+        assert!(target.try_verify().is_ok());
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn disabled_catch_all_does_not_shadow() {
+        let mock = MockState::new();
+        let catch_all = GivenBehaviour::always(0, "catch all");
+        catch_all.set_enabled(false);
+        mock.add_given_behaviour("Trait", "method", catch_all);
+        mock.add_given_behaviour("Trait", "method", GivenBehaviour::always(1, "fallback"));
+        mock.add_given_behaviour("Trait", "method", GivenBehaviour::always(2, "shadowed"));
+        assert_eq!(mock.find_shadowed_behaviours(), vec![(1, 2)]);
+    }
+
+    #[cfg(feature = "timing")]
+    #[test]
+    fn catch_all_with_ttl_does_not_shadow() {
+        let mock = MockState::new();
+        mock.add_given_behaviour("Trait", "method",
+                                 GivenBehaviour::always(0, "expiring").with_ttl(std::time::Duration::from_secs(1)));
+        mock.add_given_behaviour("Trait", "method", GivenBehaviour::always(1, "fallback"));
+        assert!(mock.find_shadowed_behaviours().is_empty());
+    }
+
+    #[test]
+    fn val_describes_expected_value() {
+        assert_eq!(ArgMatcher::<i32>::describe_matcher(&matchers::val(42)), "a value equal to 42");
+    }
+
+    #[cfg(feature = "disabled")]
+    #[test]
+    fn disabled_mock_verifies_trivially_and_records_nothing() {
+        let mut mock = MockState::new();
+        mock.should_log_calls(true);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 0, Rc::new(()), "never matched"));
+        mock.add_given_behaviour("Trait", "method", GivenBehaviour::always(1, "always"));
+        assert_eq!(mock.expect_count("Trait", "method"), 0);
+        assert_eq!(mock.given_count("Trait", "method"), 0);
+        assert_eq!(mock.record_call("Trait", "method"), 0);
+        assert!(mock.try_verify().is_ok());
+
+        let expect = ExpectBehaviour::with_times(1, 2, Rc::new(()), "unregistered");
+        expect.matched();
+        assert_eq!(expect.num_matches.get(), 0);
+        let given = GivenBehaviour::always(3, "unregistered");
+        given.matched();
+        assert_eq!(given.num_matches.get(), 0);
+        assert!(mock.invocation_timeline().is_empty());
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn unattached_mocks_number_their_matches_independently() {
+        let numbers = |mock: &MockState| -> Vec<usize> {
+            mock.invocation_timeline().iter().map(|record| record.sequence_number).collect()
+        };
+        let first = MockState::new();
+        let second = MockState::new();
+        first.add_given_behaviour("Trait", "method", GivenBehaviour::always(0, "first"));
+        second.add_given_behaviour("Trait", "method", GivenBehaviour::always(0, "second"));
+        for _ in 0..2 {
+            first.select_given("Trait", "method", |_| true).expect("behaviour selected").matched();
+            second.select_given("Trait", "method", |_| true).expect("behaviour selected").matched();
+        }
+        assert_eq!(numbers(&first), vec![0, 1]);
+        assert_eq!(numbers(&second), vec![0, 1]);
+
+        let sequence_clock = SequenceClock::new();
+        first.attach_sequence_clock(&sequence_clock);
+        second.attach_sequence_clock(&sequence_clock);
+        second.select_given("Trait", "method", |_| true).expect("behaviour selected").matched();
+        first.select_given("Trait", "method", |_| true).expect("behaviour selected").matched();
+        assert_eq!(numbers(&first), vec![0, 1, 1]);
+        assert_eq!(numbers(&second), vec![0, 1, 0]);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn on_satisfied_fires_once_for_behaviour_saturated_from_the_start() {
+        let fired = Rc::new(std::cell::Cell::new(0));
+        let mut mock = MockState::new();
+        mock.should_verify_on_drop(false);
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_at_most(2, 0, Rc::new(()), "at most twice"));
+        mock.add_expect_behaviour("Trait", "method", ExpectBehaviour::with_times(1, 1, Rc::new(()), "once"));
+        for stmt_id in 0..2 {
+            let fired = fired.clone();
+            assert!(mock.set_on_satisfied("Trait", "method", stmt_id, Box::new(move || fired.set(fired.get() + 1))));
+        }
+        assert_eq!(fired.get(), 1);
+        for _ in 0..2 {
+            mock.match_expected("Trait", "method", |b| b.stmt_id == 0);
+        }
+        assert_eq!(fired.get(), 1);
+        mock.match_expected("Trait", "method", |b| b.stmt_id == 1);
+        assert_eq!(fired.get(), 2);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn consumed_expectations_are_removed_when_adding_behaviours_in_bulk() {
+        let mut mock = MockState::new();
+        mock.should_verify_on_drop(false);
+        mock.add_expect_behaviour("Trait", "method",
+                                  ExpectBehaviour::with_times(1, 0, Rc::new(()), "once").with_remove_on_satisfy(true));
+        mock.add_expect_behaviour("Trait", "method",
+                                  ExpectBehaviour::with_at_most(1, 1, Rc::new(()), "at most once").with_remove_on_satisfy(true));
+        mock.match_expected("Trait", "method", |b| b.stmt_id == 0);
+        mock.add_expect_behaviours("Trait", "method", vec![ExpectBehaviour::with_times(1, 2, Rc::new(()), "bulk")]);
+        let stmt_ids: Vec<usize> = mock.expect_behaviours.borrow()[&("Trait", "method")].iter().map(|b| b.stmt_id).collect();
+        assert_eq!(stmt_ids, vec![1, 2]);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn unused_given_behaviours_are_reported_by_trait_and_method() {
+        let mut mock = MockState::new();
+        mock.should_verify_on_drop(false);
+        mock.should_verify_no_unused_given(true);
+        let registered = [("B", "b", "B::b"), ("A", "b", "A::b"), ("B", "a", "B::a"), ("A", "a", "A::a")];
+        for &(requested_trait, method, stmt_repr) in registered.iter() {
+            mock.add_given_behaviour(requested_trait, method, GivenBehaviour::always(0, stmt_repr));
+        }
+        let failures = mock.unsatisfied_descriptions();
+        let positions: Vec<Option<usize>> = ["A::a", "A::b", "B::a", "B::b"]
+            .iter()
+            .map(|stmt_repr| failures.iter().position(|failure| failure.contains(stmt_repr)))
+            .collect();
+        assert_eq!(positions, vec![Some(0), Some(1), Some(2), Some(3)]);
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[test]
+    fn fork_shares_clocks_and_type_matchers() {
+        let mut mock = MockState::new();
+        mock.register_type_matcher::<u32, _>(|actual: &u32| *actual > 1);
+        let sequence_clock = SequenceClock::new();
+        mock.attach_sequence_clock(&sequence_clock);
+        let clock = ManualClock::new();
+        mock.set_clock(Box::new(clock.clone()));
+        mock.add_given_behaviour("Trait", "method", GivenBehaviour::always(0, "always"));
+
+        let fork = mock.fork();
+        assert!(fork.type_matcher::<u32>().expect("type matcher copied").match_owned_args(&2));
+        clock.advance(std::time::Duration::from_secs(1));
+        assert_eq!(fork.shared().clock.borrow().now(), clock.now());
+        mock.select_given("Trait", "method", |_| true).expect("behaviour selected").matched();
+        fork.select_given("Trait", "method", |_| true).expect("behaviour selected").matched();
+        let numbers: Vec<usize> = fork.invocation_timeline().iter().map(|record| record.sequence_number).collect();
+        assert_eq!(numbers, vec![1]);
     }
 }
@@ -0,0 +1,510 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Built-in `ArgMatcher`s which can be used for matching the arguments of mocked methods.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use {ArgMatcher, SharedState};
+
+pub mod collections;
+pub mod tuples;
+pub mod variants;
+
+/// Matches any value.
+///
+/// See `anything()`.
+pub struct Anything;
+
+/// Matches regardless of the actual value, e.g., as the inner matcher of `variants::some()`.
+pub fn anything() -> Anything {
+    Anything
+}
+
+impl<'a, T: 'a> ArgMatcher<'a, T> for Anything {
+    fn match_args(&self, _actual: &'a T) -> bool {
+        true
+    }
+
+    fn describe_matcher(&self) -> String {
+        "anything".to_string()
+    }
+}
+
+/// Inverts the result of the wrapped matcher.
+///
+/// See `not()`.
+pub struct Not<M, T> {
+    inner: M,
+    _phantom: PhantomData<fn(&T)>,
+}
+
+/// Matches if the `inner` matcher does not match.
+pub fn not<'a, T: 'a, M: ArgMatcher<'a, T>>(inner: M) -> Not<M, T> {
+    Not { inner, _phantom: PhantomData }
+}
+
+impl<'a, T: 'a, M: ArgMatcher<'a, T>> ArgMatcher<'a, T> for Not<M, T> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        !self.inner.match_args(actual)
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("not {}", self.inner.describe_matcher())
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        if self.match_args(actual) {
+            None
+        } else {
+            Some(format!("expected {}, but the value matched", self.describe_matcher()))
+        }
+    }
+}
+
+/// Matches any value unequal to the forbidden one.
+///
+/// See `not_eq()`.
+pub struct NotEq<T> {
+    forbidden: T,
+}
+
+/// Matches if the actual value is not equal to `forbidden`.
+pub fn not_eq<T: PartialEq + Debug>(forbidden: T) -> NotEq<T> {
+    NotEq { forbidden }
+}
+
+impl<'a, T: PartialEq + Debug + 'a> ArgMatcher<'a, T> for NotEq<T> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        *actual != self.forbidden
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("any value except {:?}", self.forbidden)
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        if self.match_args(actual) {
+            None
+        } else {
+            Some(format!("expected {}, got {:?}", self.describe_matcher(), actual))
+        }
+    }
+}
+
+/// Matches any value equal to the wrapped one.
+///
+/// See `val()`.
+pub struct Val<T> {
+    expected: T,
+}
+
+/// Matches if the actual value is equal to `expected`.
+pub fn val<T: PartialEq + Debug>(expected: T) -> Val<T> {
+    Val { expected }
+}
+
+impl<T> From<T> for Val<T> {
+    fn from(expected: T) -> Self {
+        Val { expected }
+    }
+}
+
+impl<'a, T: PartialEq + Debug + 'a> ArgMatcher<'a, T> for Val<T> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        *actual == self.expected
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a value equal to {:?}", self.expected)
+    }
+}
+
+/// Applies the `inner` matcher to a field of the actual value.
+///
+/// See `field()`.
+pub struct FieldMatcher<P, M, U> {
+    project: P,
+    inner: M,
+    _phantom: PhantomData<fn(&U)>,
+}
+
+/// Matches if the `inner` matcher matches the field selected by `project` from the actual value.
+///
+/// E.g., `field(|user: &User| &user.id, val(42))` matches users with an `id` of `42`.
+pub fn field<'a, T: 'a, U: 'a, P, M>(project: P, inner: M) -> FieldMatcher<P, M, U>
+    where P: Fn(&'a T) -> &'a U,
+          M: ArgMatcher<'a, U>
+{
+    FieldMatcher { project, inner, _phantom: PhantomData }
+}
+
+impl<'a, T: 'a, U: 'a, P, M> ArgMatcher<'a, T> for FieldMatcher<P, M, U>
+    where P: Fn(&'a T) -> &'a U,
+          M: ArgMatcher<'a, U>
+{
+    fn match_args(&self, actual: &'a T) -> bool {
+        self.inner.match_args((self.project)(actual))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a field matching {}", self.inner.describe_matcher())
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        self.inner.explain((self.project)(actual)).map(|reason| format!("field rejected: {}", reason))
+    }
+}
+
+/// Matches any value whose `Display` representation equals the expected string.
+///
+/// See `display_eq()`.
+pub struct DisplayEq {
+    expected: String,
+}
+
+/// Matches if `actual.to_string()` is equal to `expected`.
+///
+/// This allows comparing values by their canonical string form if their `PartialEq` is unavailable or too strict.
+pub fn display_eq(expected: &str) -> DisplayEq {
+    DisplayEq { expected: expected.to_string() }
+}
+
+impl<'a, T: Display + 'a> ArgMatcher<'a, T> for DisplayEq {
+    fn match_args(&self, actual: &'a T) -> bool {
+        actual.to_string() == self.expected
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a value displayed as {:?}", self.expected)
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        let actual = actual.to_string();
+        if actual == self.expected {
+            None
+        } else {
+            Some(format!("expected a value displayed as {:?}, got {:?}", self.expected, actual))
+        }
+    }
+}
+
+/// Attaches a human-readable name to a matcher, e.g., to an otherwise opaque closure.
+///
+/// See `named_matcher()`.
+pub struct NamedMatcher<M> {
+    name: String,
+    inner: M,
+}
+
+/// Matches if the `inner` matcher matches, naming it `name` in diagnostic messages.
+///
+/// If the `inner` matcher rejects an argument, the explanation is "matcher '`name`' rejected the argument",
+/// followed by the inner matcher's own explanation if it has one.
+/// The explanation shows up in the diagnostics of unexpected calls, see `MockState::record_unexpected_call()`.
+pub fn named_matcher<M>(name: &str, inner: M) -> NamedMatcher<M> {
+    NamedMatcher { name: name.to_string(), inner }
+}
+
+impl<'a, T: 'a, M: ArgMatcher<'a, T>> ArgMatcher<'a, T> for NamedMatcher<M> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        self.inner.match_args(actual)
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("matcher '{}'", self.name)
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        if self.inner.match_args(actual) {
+            return None;
+        }
+        match self.inner.explain(actual) {
+            Some(explanation) => Some(format!("matcher '{}' rejected the argument: {}", self.name, explanation)),
+            None => Some(format!("matcher '{}' rejected the argument", self.name)),
+        }
+    }
+}
+
+/// Matches the argument by its identity instead of its value.
+///
+/// See `same_instance()`.
+pub struct SameInstance<T> {
+    ptr: *const T,
+}
+
+/// Matches if the actual argument is the very object `ptr` points to, e.g., `same_instance(&config)`.
+///
+/// Only the addresses are compared, the pointer is never dereferenced.
+/// The comparison is only meaningful while the referenced object is alive,
+/// a different object may occupy the same address once it has been dropped.
+pub fn same_instance<T>(ptr: *const T) -> SameInstance<T> {
+    SameInstance { ptr }
+}
+
+impl<'a, T: 'a> ArgMatcher<'a, T> for SameInstance<T> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        std::ptr::eq(actual, self.ptr)
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("the instance at {:p}", self.ptr)
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        if std::ptr::eq(actual, self.ptr) {
+            None
+        } else {
+            Some(format!("expected the instance at {:p}, got the one at {:p}", self.ptr, actual))
+        }
+    }
+}
+
+/// Matches depending on the index of the current call of a mocked method instead of its arguments.
+///
+/// See `MockState::on_call()`.
+pub struct OnCall<P> {
+    shared: Rc<SharedState>,
+    requested_trait: &'static str,
+    method: &'static str,
+    predicate: P,
+}
+
+impl<P: Fn(usize) -> bool> OnCall<P> {
+    pub(crate) fn new(shared: Rc<SharedState>, requested_trait: &'static str, method: &'static str, predicate: P) -> Self {
+        OnCall { shared, requested_trait, method, predicate }
+    }
+
+    /// Returns the index of the method's current call starting at `0`, `None` if no call has been recorded.
+    fn call_index(&self) -> Option<usize> {
+        self.shared
+            .call_counts
+            .borrow()
+            .get(&(self.requested_trait, self.method))
+            .and_then(|count| count.checked_sub(1))
+    }
+}
+
+impl<'a, T: 'a, P: Fn(usize) -> bool> ArgMatcher<'a, T> for OnCall<P> {
+    fn match_args(&self, _actual: &'a T) -> bool {
+        self.call_index().is_some_and(|index| (self.predicate)(index))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a call of {}::{} with an accepted index", self.requested_trait, self.method)
+    }
+
+    fn explain(&self, _actual: &'a T) -> Option<String> {
+        match self.call_index() {
+            None => Some("no call has been recorded".to_string()),
+            Some(index) if !(self.predicate)(index) => Some(format!("call index {} is not accepted", index)),
+            Some(_) => None,
+        }
+    }
+}
+
+/// A slot shared between a matcher capturing an argument and matchers comparing later arguments against it.
+///
+/// ```ignore
+/// let id = CapturedRef::new();
+/// // the argument of `read` is captured, the one of `write` must be equal to it
+/// given_read(id.capture());
+/// expect_write(id.matches_captured());
+/// ```
+pub struct CapturedRef<T> {
+    value: Rc<RefCell<Option<T>>>,
+}
+
+impl<T> Clone for CapturedRef<T> {
+    fn clone(&self) -> Self {
+        CapturedRef { value: self.value.clone() }
+    }
+}
+
+impl<T> Default for CapturedRef<T> {
+    fn default() -> Self {
+        CapturedRef { value: Rc::new(RefCell::new(None)) }
+    }
+}
+
+impl<T: Clone> CapturedRef<T> {
+    /// Creates an empty slot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the most recently captured value, `None` if nothing has been captured yet.
+    pub fn get(&self) -> Option<T> {
+        self.value.borrow().clone()
+    }
+
+    /// Returns a matcher which matches any value and stores it in the slot.
+    pub fn capture(&self) -> Capture<T> {
+        Capture { slot: self.clone() }
+    }
+
+    /// Returns a matcher which matches values equal to the captured one.
+    pub fn matches_captured(&self) -> MatchesCaptured<T> {
+        MatchesCaptured { slot: self.clone() }
+    }
+}
+
+/// Matches any value and captures it, see `CapturedRef::capture()`.
+pub struct Capture<T> {
+    slot: CapturedRef<T>,
+}
+
+impl<'a, T: Clone + 'a> ArgMatcher<'a, T> for Capture<T> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        *self.slot.value.borrow_mut() = Some(actual.clone());
+        true
+    }
+
+    fn describe_matcher(&self) -> String {
+        "any value, which is captured".to_string()
+    }
+}
+
+/// Matches values equal to a captured one, see `CapturedRef::matches_captured()`.
+///
+/// Nothing matches before a value has been captured.
+pub struct MatchesCaptured<T> {
+    slot: CapturedRef<T>,
+}
+
+impl<'a, T: PartialEq + Debug + 'a> ArgMatcher<'a, T> for MatchesCaptured<T> {
+    fn match_args(&self, actual: &'a T) -> bool {
+        self.slot.value.borrow().as_ref().is_some_and(|captured| captured == actual)
+    }
+
+    fn describe_matcher(&self) -> String {
+        match *self.slot.value.borrow() {
+            Some(ref captured) => format!("a value equal to the captured {:?}", captured),
+            None => "a value equal to the captured one".to_string(),
+        }
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        match *self.slot.value.borrow() {
+            None => Some(format!("no value has been captured yet to compare {:?} with", actual)),
+            Some(ref captured) if captured != actual => {
+                Some(format!("expected the captured {:?}, got {:?}", captured, actual))
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Matches values against the front of a queue populated by the test, see `from_queue()`.
+pub struct FromQueue<T, F> {
+    queue: Rc<RefCell<VecDeque<T>>>,
+    eq: F,
+    mismatches: RefCell<Vec<String>>,
+    last_mismatch: RefCell<Option<String>>,
+}
+
+/// Matches if the actual value equals the front of the `queue` according to `eq`, **popping** the front.
+///
+/// Each evaluation consumes one value, regardless of whether it matched, so the matcher must be evaluated exactly once
+/// per call; evaluating several behaviours with the same queue or retrying a match pops further values.
+/// This lets a test push the expected arguments while it runs instead of specifying them upfront.
+/// An empty queue never matches. Each mismatch is recorded, see `FromQueue::mismatches()`.
+/// `explain()` doesn't pop, it explains the outcome of the most recent evaluation.
+pub fn from_queue<T, F>(queue: Rc<RefCell<VecDeque<T>>>, eq: F) -> FromQueue<T, F>
+    where F: Fn(&T, &T) -> bool
+{
+    FromQueue { queue, eq, mismatches: RefCell::new(Vec::new()), last_mismatch: RefCell::new(None) }
+}
+
+impl<T, F> FromQueue<T, F> {
+    /// Returns the messages of all mismatches in the order of the evaluations.
+    pub fn mismatches(&self) -> Vec<String> {
+        self.mismatches.borrow().clone()
+    }
+}
+
+impl<'a, T: Debug + 'a, F> ArgMatcher<'a, T> for FromQueue<T, F>
+    where F: Fn(&T, &T) -> bool
+{
+    fn match_args(&self, actual: &'a T) -> bool {
+        let mismatch = match self.queue.borrow_mut().pop_front() {
+            Some(ref expected) if (self.eq)(expected, actual) => None,
+            Some(expected) => Some(format!("expected {:?} from the queue, got {:?}", expected, actual)),
+            None => Some(format!("the queue is empty, got {:?}", actual)),
+        };
+        let is_match = mismatch.is_none();
+        self.mismatches.borrow_mut().extend(mismatch.clone());
+        *self.last_mismatch.borrow_mut() = mismatch;
+        is_match
+    }
+
+    fn describe_matcher(&self) -> String {
+        match self.queue.borrow().front() {
+            Some(expected) => format!("the next queued value, currently {:?}", expected),
+            None => "the next queued value".to_string(),
+        }
+    }
+
+    fn explain(&self, _actual: &'a T) -> Option<String> {
+        self.last_mismatch.borrow().clone()
+    }
+}
+
+/// Matches string arguments against a regular expression.
+///
+/// See `matches_regex()`. Available with the `regex` feature.
+#[cfg(feature = "regex")]
+pub struct RegexMatcher {
+    regex: ::regex::Regex,
+}
+
+/// Matches if the actual string contains a match of the regular expression `pattern`.
+///
+/// The pattern is compiled once. Panics if the pattern is invalid, see `try_matches_regex()`.
+#[cfg(feature = "regex")]
+pub fn matches_regex(pattern: &str) -> RegexMatcher {
+    try_matches_regex(pattern).unwrap_or_else(|e| panic!("Invalid regular expression {:?}: {}", pattern, e))
+}
+
+/// Matches if the actual string contains a match of the regular expression `pattern`.
+///
+/// Returns an error if the pattern is invalid.
+#[cfg(feature = "regex")]
+pub fn try_matches_regex(pattern: &str) -> Result<RegexMatcher, ::regex::Error> {
+    ::regex::Regex::new(pattern).map(|regex| RegexMatcher { regex })
+}
+
+#[cfg(feature = "regex")]
+impl<'a, S: AsRef<str> + 'a> ArgMatcher<'a, S> for RegexMatcher {
+    fn match_args(&self, actual: &'a S) -> bool {
+        self.regex.is_match(actual.as_ref())
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a string matching /{}/", self.regex.as_str())
+    }
+
+    fn explain(&self, actual: &'a S) -> Option<String> {
+        if self.regex.is_match(actual.as_ref()) {
+            None
+        } else {
+            Some(format!("expected a string matching /{}/, got {:?}", self.regex.as_str(), actual.as_ref()))
+        }
+    }
+}
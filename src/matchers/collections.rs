@@ -0,0 +1,196 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Matchers for arguments which dereference to a slice, e.g., `Vec<T>` or `&[T]`.
+
+use std::fmt::Debug;
+use std::ops::Deref;
+
+use ArgMatcher;
+
+/// Matches collections containing a specific element.
+///
+/// See `contains()`.
+pub struct Contains<E> {
+    item: E,
+}
+
+/// Matches if the actual collection contains an element equal to `item`.
+pub fn contains<E: PartialEq + Debug>(item: E) -> Contains<E> {
+    Contains { item }
+}
+
+impl<'a, C, E> ArgMatcher<'a, C> for Contains<E>
+    where C: Deref<Target = [E]> + 'a,
+          E: PartialEq + Debug + 'a
+{
+    fn match_args(&self, actual: &'a C) -> bool {
+        actual.contains(&self.item)
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a collection containing {:?}", self.item)
+    }
+}
+
+/// Matches collections of a specific length.
+///
+/// See `len_eq()`.
+pub struct LenEq {
+    len: usize,
+}
+
+/// Matches if the actual collection has exactly `len` elements.
+pub fn len_eq(len: usize) -> LenEq {
+    LenEq { len }
+}
+
+impl<'a, C, E> ArgMatcher<'a, C> for LenEq
+    where C: Deref<Target = [E]> + 'a,
+          E: 'a
+{
+    fn match_args(&self, actual: &'a C) -> bool {
+        actual.len() == self.len
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a collection of length {}", self.len)
+    }
+
+    fn explain(&self, actual: &'a C) -> Option<String> {
+        if self.match_args(actual) {
+            None
+        } else {
+            let description = <Self as ArgMatcher<'a, C>>::describe_matcher(self);
+            Some(format!("expected {}, got length {}", description, actual.len()))
+        }
+    }
+}
+
+/// Matches collections whose elements all satisfy a matcher.
+///
+/// See `all()`.
+pub struct All<M> {
+    inner: M,
+}
+
+/// Matches if every element of the actual collection satisfies the `inner` matcher.
+///
+/// An empty collection always matches.
+pub fn all<M>(inner: M) -> All<M> {
+    All { inner }
+}
+
+impl<'a, C, E, M> ArgMatcher<'a, C> for All<M>
+    where C: Deref<Target = [E]> + 'a,
+          E: 'a,
+          M: ArgMatcher<'a, E>
+{
+    fn match_args(&self, actual: &'a C) -> bool {
+        actual.iter().all(|element| self.inner.match_args(element))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a collection whose elements are all {}", self.inner.describe_matcher())
+    }
+
+    fn explain(&self, actual: &'a C) -> Option<String> {
+        let description = <Self as ArgMatcher<'a, C>>::describe_matcher(self);
+        actual.iter()
+              .position(|element| !self.inner.match_args(element))
+              .map(|index| format!("expected {}, but the element at index {} is rejected", description, index))
+    }
+}
+
+/// Matches collections with at least one element satisfying a matcher.
+///
+/// See `any()`.
+pub struct Any<M> {
+    inner: M,
+}
+
+/// Matches if at least one element of the actual collection satisfies the `inner` matcher.
+///
+/// An empty collection never matches.
+pub fn any<M>(inner: M) -> Any<M> {
+    Any { inner }
+}
+
+impl<'a, C, E, M> ArgMatcher<'a, C> for Any<M>
+    where C: Deref<Target = [E]> + 'a,
+          E: 'a,
+          M: ArgMatcher<'a, E>
+{
+    fn match_args(&self, actual: &'a C) -> bool {
+        actual.iter().any(|element| self.inner.match_args(element))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a collection with any element {}", self.inner.describe_matcher())
+    }
+}
+
+/// Matches collections containing elements satisfying a list of matchers in order.
+///
+/// See `contains_in_order()`.
+pub struct ContainsInOrder<M> {
+    expected: Vec<M>,
+}
+
+/// Matches if the actual collection contains elements satisfying the `expected` matchers in their relative order.
+///
+/// The elements need not be contiguous, e.g., `[1, 2, 3, 4]` contains `eq(1)` and `eq(3)` in order.
+/// Each matcher is matched greedily against the first remaining element it accepts.
+pub fn contains_in_order<M>(expected: Vec<M>) -> ContainsInOrder<M> {
+    ContainsInOrder { expected }
+}
+
+impl<M> ContainsInOrder<M> {
+    /// Returns how many of the expected matchers are satisfied in order by the `actual` elements.
+    fn num_matched<'a, E: 'a>(&self, actual: &'a [E]) -> usize
+        where M: ArgMatcher<'a, E>
+    {
+        let mut elements = actual.iter();
+        self.expected
+            .iter()
+            .take_while(|matcher| elements.any(|element| matcher.match_args(element)))
+            .count()
+    }
+}
+
+impl<'a, C, E, M> ArgMatcher<'a, C> for ContainsInOrder<M>
+    where C: Deref<Target = [E]> + 'a,
+          E: 'a,
+          M: ArgMatcher<'a, E>
+{
+    fn match_args(&self, actual: &'a C) -> bool {
+        self.num_matched(actual) == self.expected.len()
+    }
+
+    fn describe_matcher(&self) -> String {
+        let descriptions: Vec<String> = self.expected.iter().map(|matcher| matcher.describe_matcher()).collect();
+        format!("a collection containing in order [{}]", descriptions.join(", "))
+    }
+
+    fn explain(&self, actual: &'a C) -> Option<String> {
+        let num_matched = self.num_matched(actual);
+        self.expected.get(num_matched).map(|missing| {
+            format!("matched {} of {} elements in order, no later element is {}",
+                    num_matched,
+                    self.expected.len(),
+                    missing.describe_matcher())
+        })
+    }
+}
@@ -0,0 +1,139 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Matchers for curried arguments which only constrain some positions of the tuple.
+//!
+//! ```ignore
+//! tuple_partial((Some(eq(1)), unconstrained(), Some(eq("x")), unconstrained()))
+//! ```
+
+use ArgMatcher;
+use matchers::Anything;
+
+/// Matches tuples whose constrained positions satisfy their matchers.
+///
+/// See `tuple_partial()`.
+pub struct TuplePartial<P> {
+    positions: P,
+}
+
+/// Matches if each position of the actual tuple with a `Some` matcher satisfies it, `None` positions accept anything.
+///
+/// The positions are passed as a tuple of `Option`s of matchers with two to four elements.
+/// On a mismatch the explanation names the first rejecting position.
+pub fn tuple_partial<P>(positions: P) -> TuplePartial<P> {
+    TuplePartial { positions }
+}
+
+/// Leaves a position of `tuple_partial()` unconstrained.
+///
+/// This is a `None` whose matcher type can be inferred.
+pub fn unconstrained() -> Option<Anything> {
+    None
+}
+
+/// Returns `true` iff the position is unconstrained or its matcher accepts the `actual` element.
+fn accepts<'a, T: 'a, M: ArgMatcher<'a, T>>(matcher: &Option<M>, actual: &'a T) -> bool {
+    matcher.as_ref().is_none_or(|matcher| matcher.match_args(actual))
+}
+
+/// Returns why the matcher of the position at `index` rejects the `actual` element, `None` if it accepts it.
+fn rejection<'a, T: 'a, M: ArgMatcher<'a, T>>(index: usize, matcher: &Option<M>, actual: &'a T) -> Option<String> {
+    match *matcher {
+        Some(ref matcher) if !matcher.match_args(actual) => {
+            let reason = matcher.explain(actual).unwrap_or_else(|| format!("expected {}", matcher.describe_matcher()));
+            Some(format!("position {} rejected the argument: {}", index, reason))
+        }
+        _ => None,
+    }
+}
+
+/// Describes the constrained positions, e.g., `(0: 1, 2: "x")`.
+fn describe_positions(descriptions: &[Option<String>]) -> String {
+    let constrained: Vec<String> = descriptions.iter()
+                                               .enumerate()
+                                               .filter_map(|(index, d)| d.as_ref().map(|d| format!("{}: {}", index, d)))
+                                               .collect();
+    format!("a tuple with positions ({})", constrained.join(", "))
+}
+
+impl<'a, A: 'a, B: 'a, MA, MB> ArgMatcher<'a, (A, B)> for TuplePartial<(Option<MA>, Option<MB>)>
+    where MA: ArgMatcher<'a, A>,
+          MB: ArgMatcher<'a, B>
+{
+    fn match_args(&self, actual: &'a (A, B)) -> bool {
+        accepts(&self.positions.0, &actual.0) && accepts(&self.positions.1, &actual.1)
+    }
+
+    fn describe_matcher(&self) -> String {
+        describe_positions(&[self.positions.0.as_ref().map(|m| m.describe_matcher()),
+                             self.positions.1.as_ref().map(|m| m.describe_matcher())])
+    }
+
+    fn explain(&self, actual: &'a (A, B)) -> Option<String> {
+        rejection(0, &self.positions.0, &actual.0).or_else(|| rejection(1, &self.positions.1, &actual.1))
+    }
+}
+
+impl<'a, A: 'a, B: 'a, C: 'a, MA, MB, MC> ArgMatcher<'a, (A, B, C)>
+    for TuplePartial<(Option<MA>, Option<MB>, Option<MC>)>
+    where MA: ArgMatcher<'a, A>,
+          MB: ArgMatcher<'a, B>,
+          MC: ArgMatcher<'a, C>
+{
+    fn match_args(&self, actual: &'a (A, B, C)) -> bool {
+        accepts(&self.positions.0, &actual.0) && accepts(&self.positions.1, &actual.1) &&
+        accepts(&self.positions.2, &actual.2)
+    }
+
+    fn describe_matcher(&self) -> String {
+        describe_positions(&[self.positions.0.as_ref().map(|m| m.describe_matcher()),
+                             self.positions.1.as_ref().map(|m| m.describe_matcher()),
+                             self.positions.2.as_ref().map(|m| m.describe_matcher())])
+    }
+
+    fn explain(&self, actual: &'a (A, B, C)) -> Option<String> {
+        rejection(0, &self.positions.0, &actual.0)
+            .or_else(|| rejection(1, &self.positions.1, &actual.1))
+            .or_else(|| rejection(2, &self.positions.2, &actual.2))
+    }
+}
+
+impl<'a, A: 'a, B: 'a, C: 'a, D: 'a, MA, MB, MC, MD> ArgMatcher<'a, (A, B, C, D)>
+    for TuplePartial<(Option<MA>, Option<MB>, Option<MC>, Option<MD>)>
+    where MA: ArgMatcher<'a, A>,
+          MB: ArgMatcher<'a, B>,
+          MC: ArgMatcher<'a, C>,
+          MD: ArgMatcher<'a, D>
+{
+    fn match_args(&self, actual: &'a (A, B, C, D)) -> bool {
+        accepts(&self.positions.0, &actual.0) && accepts(&self.positions.1, &actual.1) &&
+        accepts(&self.positions.2, &actual.2) && accepts(&self.positions.3, &actual.3)
+    }
+
+    fn describe_matcher(&self) -> String {
+        describe_positions(&[self.positions.0.as_ref().map(|m| m.describe_matcher()),
+                             self.positions.1.as_ref().map(|m| m.describe_matcher()),
+                             self.positions.2.as_ref().map(|m| m.describe_matcher()),
+                             self.positions.3.as_ref().map(|m| m.describe_matcher())])
+    }
+
+    fn explain(&self, actual: &'a (A, B, C, D)) -> Option<String> {
+        rejection(0, &self.positions.0, &actual.0)
+            .or_else(|| rejection(1, &self.positions.1, &actual.1))
+            .or_else(|| rejection(2, &self.positions.2, &actual.2))
+            .or_else(|| rejection(3, &self.positions.3, &actual.3))
+    }
+}
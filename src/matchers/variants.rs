@@ -0,0 +1,175 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Matchers for `Option` and `Result` arguments and for the variants of other enums.
+
+use std::fmt::Debug;
+
+use ArgMatcher;
+
+/// Matches `Some` values whose content satisfies the wrapped matcher.
+///
+/// See `some()`.
+pub struct IsSome<M> {
+    inner: M,
+}
+
+/// Matches if the actual value is `Some` and its content satisfies the `inner` matcher.
+///
+/// Use `some(anything())` to match any `Some` value.
+pub fn some<M>(inner: M) -> IsSome<M> {
+    IsSome { inner }
+}
+
+impl<'a, T: 'a, M: ArgMatcher<'a, T>> ArgMatcher<'a, Option<T>> for IsSome<M> {
+    fn match_args(&self, actual: &'a Option<T>) -> bool {
+        actual.as_ref().is_some_and(|value| self.inner.match_args(value))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("Some containing {}", self.inner.describe_matcher())
+    }
+
+    fn explain(&self, actual: &'a Option<T>) -> Option<String> {
+        match *actual {
+            Some(ref value) => self.inner.explain(value),
+            None => Some("expected Some, got None".to_string()),
+        }
+    }
+}
+
+/// Matches `None` values.
+///
+/// See `none()`.
+pub struct IsNone;
+
+/// Matches if the actual value is `None`.
+pub fn none() -> IsNone {
+    IsNone
+}
+
+impl<'a, T: 'a> ArgMatcher<'a, Option<T>> for IsNone {
+    fn match_args(&self, actual: &'a Option<T>) -> bool {
+        actual.is_none()
+    }
+
+    fn describe_matcher(&self) -> String {
+        "None".to_string()
+    }
+
+    fn explain(&self, actual: &'a Option<T>) -> Option<String> {
+        actual.as_ref().map(|_| "expected None, got Some".to_string())
+    }
+}
+
+/// Matches `Ok` values whose content satisfies the wrapped matcher.
+///
+/// See `ok()`.
+pub struct IsOk<M> {
+    inner: M,
+}
+
+/// Matches if the actual value is `Ok` and its content satisfies the `inner` matcher.
+pub fn ok<M>(inner: M) -> IsOk<M> {
+    IsOk { inner }
+}
+
+impl<'a, T: 'a, E: 'a, M: ArgMatcher<'a, T>> ArgMatcher<'a, Result<T, E>> for IsOk<M> {
+    fn match_args(&self, actual: &'a Result<T, E>) -> bool {
+        actual.as_ref().ok().is_some_and(|value| self.inner.match_args(value))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("Ok containing {}", self.inner.describe_matcher())
+    }
+
+    fn explain(&self, actual: &'a Result<T, E>) -> Option<String> {
+        match *actual {
+            Ok(ref value) => self.inner.explain(value),
+            Err(_) => Some("expected Ok, got Err".to_string()),
+        }
+    }
+}
+
+/// Matches `Err` values whose error satisfies the wrapped matcher.
+///
+/// See `err()`.
+pub struct IsErr<M> {
+    inner: M,
+}
+
+/// Matches if the actual value is `Err` and its error satisfies the `inner` matcher.
+///
+/// Use `err(anything())` to match any error.
+pub fn err<M>(inner: M) -> IsErr<M> {
+    IsErr { inner }
+}
+
+impl<'a, T: 'a, E: 'a, M: ArgMatcher<'a, E>> ArgMatcher<'a, Result<T, E>> for IsErr<M> {
+    fn match_args(&self, actual: &'a Result<T, E>) -> bool {
+        actual.as_ref().err().is_some_and(|error| self.inner.match_args(error))
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("Err containing {}", self.inner.describe_matcher())
+    }
+
+    fn explain(&self, actual: &'a Result<T, E>) -> Option<String> {
+        match *actual {
+            Ok(_) => Some("expected Err, got Ok".to_string()),
+            Err(ref error) => self.inner.explain(error),
+        }
+    }
+}
+
+/// Matches enum values by their variant, see `discriminant_eq()`.
+pub struct DiscriminantEq<P, D> {
+    project: P,
+    expected: D,
+}
+
+/// Matches if `project` maps the actual value to the `expected` discriminant.
+///
+/// The library can't inspect user-defined enums, so `project` maps each value to a comparable tag, e.g.,
+/// `discriminant_eq(|e: &Event| std::mem::discriminant(e), std::mem::discriminant(&Event::Closed))`
+/// or a closure returning the variant's name. This avoids constructing dummy payloads just to match on the variant.
+pub fn discriminant_eq<T, P, D>(project: P, expected: D) -> DiscriminantEq<P, D>
+    where P: Fn(&T) -> D,
+          D: PartialEq + Debug
+{
+    DiscriminantEq { project, expected }
+}
+
+impl<'a, T: 'a, P, D> ArgMatcher<'a, T> for DiscriminantEq<P, D>
+    where P: Fn(&T) -> D,
+          D: PartialEq + Debug
+{
+    fn match_args(&self, actual: &'a T) -> bool {
+        (self.project)(actual) == self.expected
+    }
+
+    fn describe_matcher(&self) -> String {
+        format!("a value of the variant {:?}", self.expected)
+    }
+
+    fn explain(&self, actual: &'a T) -> Option<String> {
+        let discriminant = (self.project)(actual);
+        if discriminant == self.expected {
+            None
+        } else {
+            Some(format!("expected the variant {:?}, got {:?}", self.expected, discriminant))
+        }
+    }
+}
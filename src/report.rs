@@ -0,0 +1,148 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Data structures describing the outcome of a mock's verification.
+
+use BehaviourSummary;
+
+/// The outcome of verifying the behaviours of a mock.
+///
+/// The report collects the messages of all *failures*, i.e., conditions causing the verification to fail,
+/// and of all *warnings*, i.e., suspicious conditions which do not fail the verification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct VerificationReport {
+    failures: Vec<String>,
+    warnings: Vec<String>,
+    hidden_failures: usize,
+    unsatisfied: Vec<BehaviourSummary>,
+}
+
+impl VerificationReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` iff the report contains no failures, including hidden ones.
+    pub fn is_satisfied(&self) -> bool {
+        self.failures.is_empty() && self.hidden_failures == 0
+    }
+
+    /// Returns the messages of the failures in the order they have been detected.
+    pub fn failures(&self) -> &[String] {
+        &self.failures
+    }
+
+    /// Returns the messages of the warnings in the order they have been detected.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Returns the number of failures whose messages are hidden by the mock's report filter.
+    pub fn num_hidden_failures(&self) -> usize {
+        self.hidden_failures
+    }
+
+    /// Returns the summaries of the unsatisfied *expected* behaviours which are not hidden by the report filter.
+    pub fn unsatisfied_behaviours(&self) -> &[BehaviourSummary] {
+        &self.unsatisfied
+    }
+
+    pub(crate) fn add_failure(&mut self, message: String) {
+        self.failures.push(message);
+    }
+
+    pub(crate) fn add_warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    pub(crate) fn add_hidden_failure(&mut self) {
+        self.hidden_failures += 1;
+    }
+
+    pub(crate) fn add_unsatisfied(&mut self, summary: BehaviourSummary) {
+        self.unsatisfied.push(summary);
+    }
+
+    /// Converts the report into a result of a **galvanic-assert** matcher.
+    ///
+    /// The result is `Failed` iff the report contains failures, the failures are listed in the result's reason.
+    #[cfg(feature = "galvanic_assert_integration")]
+    pub fn into_match_result(self) -> ::galvanic_assert::MatchResult {
+        let name = "mock behaviours are satisfied".to_string();
+        if self.is_satisfied() {
+            ::galvanic_assert::MatchResult::Matched { name }
+        } else {
+            ::galvanic_assert::MatchResult::Failed { name, reason: self.failures.join("\n") }
+        }
+    }
+}
+
+/// The error returned if a mock fails a verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The mock's behaviours are not satisfied.
+    Unsatisfied(Vec<String>),
+    /// The mock's behaviours were still unsatisfied once the deadline of an eventual verification elapsed.
+    Timeout {
+        /// The requested timeout of the verification.
+        timeout: std::time::Duration,
+        /// The failures observed at the deadline.
+        failures: Vec<String>,
+    },
+}
+
+impl VerificationError {
+    /// Creates an error from the messages of the detected failures.
+    pub fn new(failures: Vec<String>) -> Self {
+        VerificationError::Unsatisfied(failures)
+    }
+
+    /// Creates an error from the messages of the failures observed once the `timeout` elapsed.
+    pub fn timeout(timeout: std::time::Duration, failures: Vec<String>) -> Self {
+        VerificationError::Timeout { timeout, failures }
+    }
+
+    /// Returns the messages of the failures in the order they have been detected.
+    pub fn failures(&self) -> &[String] {
+        match *self {
+            VerificationError::Unsatisfied(ref failures) | VerificationError::Timeout { ref failures, .. } => failures,
+        }
+    }
+
+    /// Returns `true` iff the verification failed because its deadline elapsed.
+    pub fn is_timeout(&self) -> bool {
+        match *self {
+            VerificationError::Timeout { .. } => true,
+            VerificationError::Unsatisfied(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            VerificationError::Unsatisfied(ref failures) => {
+                write!(f, "Verification of the mock failed:\n{}", failures.join("\n"))
+            }
+            VerificationError::Timeout { timeout, ref failures } => {
+                write!(f, "Verification of the mock timed out after {:?}:\n{}", timeout, failures.join("\n"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
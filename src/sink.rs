@@ -0,0 +1,71 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Destinations for the diagnostic messages emitted by a mock.
+
+/// Receives the diagnostic messages of a mock, e.g., during verification.
+pub trait MessageSink {
+    /// Reports a message describing why the verification of a mock fails.
+    fn failure(&self, message: &str);
+
+    /// Reports a message about a suspicious state of the mock which does not fail the verification.
+    fn warning(&self, message: &str);
+}
+
+/// Prints all messages to `stderr`.
+///
+/// This is the default sink of a `MockState` unless the `log_integration` feature is enabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StderrSink;
+
+impl MessageSink for StderrSink {
+    fn failure(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+
+    fn warning(&self, message: &str) {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+/// Forwards all messages to the **log** crate, failures at the `error` and warnings at the `warn` level.
+///
+/// This is the default sink of a `MockState` if the `log_integration` feature is enabled.
+#[cfg(feature = "log_integration")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogSink;
+
+#[cfg(feature = "log_integration")]
+impl MessageSink for LogSink {
+    fn failure(&self, message: &str) {
+        log::error!("{}", message);
+    }
+
+    fn warning(&self, message: &str) {
+        log::warn!("{}", message);
+    }
+}
+
+/// Returns the sink used by a new `MockState`.
+pub(crate) fn default_sink() -> Box<dyn MessageSink> {
+    #[cfg(feature = "log_integration")]
+    {
+        Box::new(LogSink)
+    }
+    #[cfg(not(feature = "log_integration"))]
+    {
+        Box::new(StderrSink)
+    }
+}
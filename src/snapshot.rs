@@ -0,0 +1,124 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Snapshots of the match counts of a mock's behaviours and the differences between them.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use BehaviourKind;
+
+/// Identifies a behaviour within a mock by its trait, method, kind, and `stmt_id`.
+pub type BehaviourKey = (&'static str, &'static str, BehaviourKind, usize);
+
+/// The match counts of a mock's behaviours at a point in time.
+///
+/// See `MockState::snapshot()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockStateSnapshot {
+    num_matches: BTreeMap<BehaviourKey, usize>,
+}
+
+impl MockStateSnapshot {
+    /// Creates an empty snapshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn insert(&mut self, key: BehaviourKey, num_matches: usize) {
+        self.num_matches.insert(key, num_matches);
+    }
+
+    /// Returns how often the behaviour has been matched, `None` if the behaviour is not part of the snapshot.
+    pub fn num_matches(&self, key: &BehaviourKey) -> Option<usize> {
+        self.num_matches.get(key).cloned()
+    }
+
+    /// Returns the changes from this snapshot to the `other` snapshot, ordered by the behaviours' keys.
+    ///
+    /// Behaviours with the same match count in both snapshots are omitted.
+    pub fn diff(&self, other: &MockStateSnapshot) -> Vec<SnapshotDelta> {
+        let mut deltas = Vec::new();
+        for (&key, &before) in &self.num_matches {
+            match other.num_matches.get(&key) {
+                Some(&after) if after != before => deltas.push(SnapshotDelta::Matched { key, before, after }),
+                Some(_) => {}
+                None => deltas.push(SnapshotDelta::Removed { key, num_matches: before }),
+            }
+        }
+        for (&key, &after) in &other.num_matches {
+            if !self.num_matches.contains_key(&key) {
+                deltas.push(SnapshotDelta::Added { key, num_matches: after });
+            }
+        }
+        deltas.sort_by_key(|delta| delta.key());
+        deltas
+    }
+}
+
+/// The match counts of a mock's behaviours and its invocation counts, which can be written back to the mock.
+///
+/// Only the counts are saved, any other state of the behaviours is left as is when restoring them. See `MockState::save_counts()` and `MockState::restore_counts()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CountSnapshot {
+    pub(crate) num_matches: BTreeMap<BehaviourKey, usize>,
+    pub(crate) call_counts: BTreeMap<(&'static str, &'static str), usize>,
+}
+
+/// A change of a single behaviour between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotDelta {
+    /// The behaviour's match count has changed from `before` to `after`.
+    Matched { key: BehaviourKey, before: usize, after: usize },
+    /// The behaviour has been added, it has been matched `num_matches` times in the later snapshot.
+    Added { key: BehaviourKey, num_matches: usize },
+    /// The behaviour has been removed, it had been matched `num_matches` times in the earlier snapshot.
+    Removed { key: BehaviourKey, num_matches: usize },
+}
+
+impl SnapshotDelta {
+    /// Returns the key of the changed behaviour.
+    pub fn key(&self) -> BehaviourKey {
+        match *self {
+            SnapshotDelta::Matched { key, .. } |
+            SnapshotDelta::Added { key, .. } |
+            SnapshotDelta::Removed { key, .. } => key,
+        }
+    }
+}
+
+impl fmt::Display for SnapshotDelta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (requested_trait, method, kind, stmt_id) = self.key();
+        let kind = match kind {
+            BehaviourKind::Given => "given",
+            BehaviourKind::Expect => "expected",
+        };
+        match *self {
+            SnapshotDelta::Matched { before, after, .. } => {
+                write!(f, "{}::{} ({} behaviour {}): matched {} -> {} times",
+                       requested_trait, method, kind, stmt_id, before, after)
+            }
+            SnapshotDelta::Added { num_matches, .. } => {
+                write!(f, "{}::{} ({} behaviour {}): added, matched {} times",
+                       requested_trait, method, kind, stmt_id, num_matches)
+            }
+            SnapshotDelta::Removed { num_matches, .. } => {
+                write!(f, "{}::{} ({} behaviour {}): removed, had been matched {} times",
+                       requested_trait, method, kind, stmt_id, num_matches)
+            }
+        }
+    }
+}
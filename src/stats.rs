@@ -0,0 +1,47 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Match statistics aggregated across several mocks, e.g., the forks of a parameterized test.
+
+use std::collections::HashMap;
+
+/// The number of matches of each trait's method summed up across all mocks sharing the statistics.
+///
+/// See `MockState::fork_with_shared_stats()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregateStats {
+    num_matches: HashMap<(&'static str, &'static str), usize>,
+}
+
+impl AggregateStats {
+    /// Creates empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_match(&mut self, requested_trait: &'static str, method: &'static str) {
+        *self.num_matches.entry((requested_trait, method)).or_insert(0) += 1;
+    }
+
+    /// Returns how often behaviours of the trait's method have been matched across all mocks.
+    pub fn total(&self, requested_trait: &'static str, method: &'static str) -> usize {
+        self.num_matches.get(&(requested_trait, method)).cloned().unwrap_or(0)
+    }
+
+    /// Returns how often behaviours of any method have been matched across all mocks.
+    pub fn grand_total(&self) -> usize {
+        self.num_matches.values().sum()
+    }
+}
@@ -0,0 +1,70 @@
+/* Copyright 2017 Christopher Bacher
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Reusable sets of behaviours which can be applied to several mocks.
+
+use std::rc::Rc;
+
+use {ExpectBehaviour, GivenBehaviour, MockControl, MockState};
+
+/// Creates a fresh *given* behaviour.
+type GivenFactory = Rc<dyn Fn() -> GivenBehaviour>;
+/// Creates a fresh *expected* behaviour.
+type ExpectFactory = Rc<dyn Fn() -> ExpectBehaviour>;
+
+/// A base configuration of behaviours shared by several mocks, e.g., by the tests of a large suite.
+///
+/// Unlike `MockState::merge_from()`, which copies the definitions of existing behaviours,
+/// the template stores factories and creates new behaviours whenever it is applied.
+#[derive(Clone, Default)]
+pub struct MockTemplate {
+    given_factories: Vec<(&'static str, &'static str, GivenFactory)>,
+    expect_factories: Vec<(&'static str, &'static str, ExpectFactory)>,
+}
+
+impl MockTemplate {
+    /// Creates an empty template.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a factory of *given* behaviours for the trait's method.
+    pub fn with_given<F>(mut self, requested_trait: &'static str, method: &'static str, factory: F) -> Self
+        where F: Fn() -> GivenBehaviour + 'static
+    {
+        self.given_factories.push((requested_trait, method, Rc::new(factory)));
+        self
+    }
+
+    /// Adds a factory of *expected* behaviours for the trait's method.
+    pub fn with_expect<F>(mut self, requested_trait: &'static str, method: &'static str, factory: F) -> Self
+        where F: Fn() -> ExpectBehaviour + 'static
+    {
+        self.expect_factories.push((requested_trait, method, Rc::new(factory)));
+        self
+    }
+
+    /// Registers a fresh instance of each of the template's behaviours with the mock state.
+    ///
+    /// The behaviours are added in the order of their factories after the already registered behaviours.
+    pub fn apply_to(&self, state: &MockState) {
+        for &(requested_trait, method, ref factory) in self.given_factories.iter() {
+            state.add_given_behaviour(requested_trait, method, factory());
+        }
+        for &(requested_trait, method, ref factory) in self.expect_factories.iter() {
+            state.add_expect_behaviour(requested_trait, method, factory());
+        }
+    }
+}